@@ -1,36 +1,68 @@
 use super::*;
 use rand::seq::SliceRandom;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
 use std::fmt;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FishGame {
     pub river: Deck,
     pub players: Vec<Player>,
+    /// Drives the computer players, kept here so a saved game resumes with it
+    pub rng: Pcg64Mcg,
+    /// Everything that has happened so far
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub log: EventLog,
 }
 
 impl FishGame {
     pub fn new(n_players: usize) -> DealResult<Self> {
+        let rng = Pcg64Mcg::from_rng(rand::thread_rng()).expect("seed rng");
+        FishGame::with_river(n_players, Deck::new(), rng)
+    }
+
+    /// A game dealt from this river, in its order
+    pub fn with_river(n_players: usize, river: Deck, rng: Pcg64Mcg) -> DealResult<Self> {
         let mut players = Vec::new();
         players.push(Player::new("Human0", Human));
         for n in 1..n_players {
             players.push(Player::new(&format!("Computer{}", n), Computer));
         }
 
-        Ok(FishGame {
-            river: Deck::new(),
+        FishGame {
+            river,
             players,
+            rng,
+            log: EventLog::new(),
         }
-        .first_deal()?)
+        .first_deal()
+    }
+
+    /// Five cards to each player in one packet, starting with the human
+    pub fn pattern(n_players: usize) -> DealPattern {
+        DealPattern::packets(n_players, &[5])
     }
 
-    // Deal 5 cards to each player
     fn first_deal(mut self) -> DealResult<Self> {
-        for player in self.players.iter_mut() {
-            for _i in 0..5 {
-                self.river.deal(0, player)?;
+        self.log.push(Event::Started {
+            players: self.players.iter().map(|p| p.name.clone()).collect(),
+            stock: self.river.len(),
+        });
+
+        let pattern = FishGame::pattern(self.players.len());
+        for destination in pattern.destinations(self.river.len())? {
+            if let Destination::Seat(n) = destination {
+                self.river.deal(0, &mut self.players[n])?;
+            }
+        }
+
+        for (n, player) in self.players.iter_mut().enumerate() {
+            self.log.push(Event::Dealt { player: n, cards: player.hand.cards().copied().collect() });
+
+            if let Some(event) = discard_pairs(n, player)? {
+                self.log.push(event);
             }
-            player.discard_pairs()?;
         }
 
         Ok(self)
@@ -40,131 +72,162 @@ impl FishGame {
         self.players.iter().any(|p| p.hand.is_empty())
     }
 
-    pub fn turn(&mut self, index: &PlayerIndex) -> Result<()> {
-        let player_type = self.players[index.current].player_type;
+    pub fn turn<U: Ui>(&mut self, turns: &TurnOrder, ui: &mut U) -> Result<()> {
+        let (player, target) = (turns.current(), turns.next());
+        let player_type = self.players[player].player_type;
         let value = match player_type {
-            Human => human_ask_value(
-                    &mut stdout(),
-                    &self.players[index.current],
-                    &self.players[index.next],
-                ),
-            Computer => computer_ask_value(&self.players[index.current]),
+            Human => ui.ask_value(self, turns)?,
+            Computer => computer_ask_value(&self.players[player], &mut self.rng),
         };
 
-        let matches = self.players[index.next].match_cards_from_value(value);
+        self.emit(Event::Asked { player, target, value }, ui);
+        if player_type == Computer {
+            ui.pause();
+        }
+
+        let matches = self.players[target].match_cards_from_value(value);
 
         match matches.first() {
-            Some((i, card)) => {
-                println!("Here you go! [{}]", card);
-                let card = self.players[index.next].give(*i)?;
-                self.players[index.current].take(card);
+            Some((i, _card)) => {
+                let card = self.players[target].give(*i)?;
+                self.players[player].take(card);
+                self.emit(Event::Gave { from: target, to: player, cards: vec![card] }, ui);
+            }
+            None if self.river.is_empty() => {
+                ui.event("The river is empty!");
             }
             None => {
-                let card = self.go_fish(&player_type);
-                println!("Caught one! [{}]", card);
-                self.players[index.current].take(card);
+                let card = self.go_fish(&player_type, ui)?;
+                self.players[player].take(card);
+                self.emit(Event::WentFishing { player, card }, ui);
             }
         }
 
-        self.players[index.current].discard_pairs()?;
+        if let Some(event) = discard_pairs(player, &mut self.players[player])? {
+            self.emit(event, ui);
+        }
 
         Ok(())
     }
 
-    fn go_fish(&mut self, player_type: &PlayerType) -> Card {
-        let index = match player_type {
-            Human => ask_user_index(&mut stdout(), self.river.len()),
-            Computer => computer_ask_index(self.river.len()),
-        };
-
-        match self.river.give(index) {
-            Ok(card) => card,
-            Err(_) => {
-                eprintln!("Invalid selection!");
-                self.go_fish(player_type)
-            }
+    /// Where every card is: the river, and each player's hand and pairs
+    pub fn census(&self) -> Census {
+        let mut census = Census::new();
+        census.count("river", &self.river);
+        for player in self.players.iter() {
+            census.count(&player.name, &player.hand)
+                .count(&format!("{} pairs", player.name), &player.paired);
         }
+        census
     }
-}
 
-fn ask_user_index<W: Write>(w: &mut W, limit: usize) -> usize {
-    write!(w, "Go fish! [0-{}]: ", limit - 1).expect("write");
-    w.flush().expect("write");
+    /// The player with the most pairs
+    pub fn leader(&self) -> Option<usize> {
+        self.players
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.paired.len().cmp(&b.paired.len()))
+            .map(|(i, _)| i)
+    }
 
-    let mut input = String::new();
-    stdin().read_line(&mut input).expect("stdin");
+    /// Record the end of the game
+    pub fn finish<U: Ui>(&mut self, ui: &mut U) {
+        self.emit(Event::GameOver { winner: self.leader() }, ui);
+    }
 
-    match input.trim().parse::<usize>() {
-        Ok(u) => {
-            if u < limit {
-                u
-            } else {
-                eprintln!("Index out of bounds!");
-                ask_user_index(w, limit)
+    /// Tell the player what happened and record it
+    fn emit<U: Ui>(&mut self, event: Event, ui: &mut U) {
+        let name = |n: usize| &self.players[n].name;
+        let message = match &event {
+            Event::Asked { player, value, .. } => Some(format!(
+                "{}: Do you have any {}?",
+                name(*player),
+                Language::English.value_plural(*value),
+            )),
+            Event::Gave { from, cards, .. } => {
+                Some(format!("{}: Here you go! {:?}", name(*from), Hand::from(cards.clone())))
+            }
+            Event::WentFishing { card, .. } => Some(format!("Caught one! [{}]", card)),
+            Event::PairsDiscarded { player, cards } => {
+                Some(format!("{} paired {:?}", name(*player), Hand::from(cards.clone())))
             }
+            Event::GameOver { winner: Some(player) } => Some(format!("{} wins!", name(*player))),
+            _ => None,
+        };
+
+        if let Some(message) = message {
+            ui.event(&message);
         }
-        Err(_) => ask_user_index(w, limit),
+        self.log.push(event);
     }
-}
-
-fn human_ask_value<W: Write>(w: &mut W, player: &Player, next: &Player) -> Value {
-    write!(w, "{}: Ask {} for a card value: ", player.name, next.name).expect("write");
-    w.flush().expect("write");
 
-    let mut input = String::new();
-    stdin().read_line(&mut input).expect("stdin");
+    fn go_fish<U: Ui>(&mut self, player_type: &PlayerType, ui: &mut U) -> Result<Card> {
+        let index = match player_type {
+            Human => ui.ask_index(self)?,
+            Computer => {
+                ui.event("Going fishing!");
+                ui.pause();
+                computer_ask_index(self.river.len(), &mut self.rng)
+            }
+        };
 
-    match Value::try_from(input.trim()) {
-        Ok(v) => {
-            if player.has_value(v) {
-                v
-            } else {
-                writeln!(w, "You don't have that card!").expect("write");
-                human_ask_value(w, player, next)
+        match self.river.give(index) {
+            Ok(card) => Ok(card),
+            Err(_) => {
+                ui.event("Invalid selection!");
+                self.go_fish(player_type, ui)
             }
         }
-        Err(_) => {
-            writeln!(w, "Invalid card value!").expect("write");
-            human_ask_value(w, player, next)
-        }
     }
 }
 
-fn computer_ask_value(player: &Player) -> Value {
+/// Discard pairs, returning the event if there were any
+fn discard_pairs(n: usize, player: &mut Player) -> DealResult<Option<Event>> {
+    let before = player.paired.len();
+    player.discard_pairs()?;
+
+    let cards: Vec<Card> = player.paired.cards().skip(before).copied().collect();
+    if cards.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(Event::PairsDiscarded { player: n, cards }))
+    }
+}
+
+fn computer_ask_value<R: Rng>(player: &Player, rng: &mut R) -> Value {
     match player
         .hand
         .cards()
         .collect::<Vec<_>>()
-        .choose(&mut rand::thread_rng())
+        .choose(rng)
     {
-        Some(card) => {
-            println!("Do you have a {:?}?", card.value);
-            sleep(2);
-            card.value
-        },
+        Some(card) => card.value,
         None => panic!("Empty hand!"),
     }
 }
 
-fn computer_ask_index(limit: usize) -> usize {
-    println!("Going fishing!");
-    sleep(2);
-    rand::thread_rng().gen_range(0, limit)
+fn computer_ask_index<R: Rng>(limit: usize, rng: &mut R) -> usize {
+    rng.gen_range(0, limit)
 }
 
 impl fmt::Display for FishGame {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let row = Renderer::default();
+        let fan = Renderer { spread: Spread::Fan, ..row };
+
         writeln!(f, "River: [0-{}]\n", self.river.len())?;
 
         for player in self.players.iter() {
             match player.player_type {
                 Human => {
-                    writeln!(f, "{}: {:?}", player.name, player.hand)?;
-                    writeln!(f, "{}: {:?}", "Paired", player.paired)?;
+                    writeln!(f, "{}:", player.name)?;
+                    writeln!(f, "{}", row.cards(&player.hand))?;
+                    writeln!(f, "Paired: {:?}", player.paired)?;
                 },
                 Computer => {
                     writeln!(f, "{}: [{}]", player.name, player.hand.len())?;
-                    writeln!(f, "{}: [{}]", "Paired", player.paired.len())?;
+                    writeln!(f, "{}", fan.backs(player.hand.len()))?;
+                    writeln!(f, "Paired: [{}]", player.paired.len())?;
                 },
             }
 
@@ -174,7 +237,3 @@ impl fmt::Display for FishGame {
         Ok(())
     }
 }
-
-fn sleep(secs: u64) {
-    std::thread::sleep(std::time::Duration::from_secs(secs))
-}
@@ -1,21 +1,55 @@
 use cards::*;
 
 use std::collections::HashMap;
-use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt;
 use std::io::{stdin, stdout, Write};
 
 mod game;
 mod player;
+#[cfg(feature = "save")]
+mod save;
+mod ui;
+
+#[cfg(test)]
+mod test;
 
 use game::*;
 use player::*;
+use ui::*;
 
 type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
 fn main() -> Result<()> {
-    match play_game(2) {
+    let args: Vec<String> = std::env::args().collect();
+    let resume = args.iter()
+        .position(|arg| arg == "--resume")
+        .map(|i| args.get(i + 1).cloned().unwrap_or_else(|| String::from("gofish.save.json")));
+
+    let game = match resume {
+        Some(path) => resume_game(&path),
+        None => FishGame::new(2)
+            .map(|game| (game, TurnOrder::new(2)))
+            .map_err(|e| e.into()),
+    };
+
+    let game = game.and_then(|(game, turns)| {
+        if args.iter().any(|arg| arg == "--tui") {
+            play_tui(game, turns)
+        } else {
+            play_game(game, turns, &mut Console)
+        }
+    });
+
+    let game = game.and_then(|game| {
+        if let Some(i) = args.iter().position(|arg| arg == "--record") {
+            let path = args.get(i + 1).ok_or("--record needs a file")?;
+            record(path, &game.log)?;
+        }
+        Ok(game)
+    });
+
+    match game.and_then(winner) {
         Ok(player) => println!("Winner: {:?}", player),
         Err(e) => eprintln!("{}", e),
     }
@@ -23,18 +57,51 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn play_game(n_players: usize) -> Result<Player> {
-    let mut game = FishGame::new(n_players)?;
+#[cfg(feature = "save")]
+fn resume_game(path: &str) -> Result<(FishGame, TurnOrder)> {
+    save::load(path)
+}
+
+#[cfg(not(feature = "save"))]
+fn resume_game(_path: &str) -> Result<(FishGame, TurnOrder)> {
+    Err("Resuming needs the `save` feature".into())
+}
 
-    let mut player_index = PlayerIndex::new(game.players.len());
-    while !game.has_empty_hand() {
-        println!("{}", &game);
-        game.turn(&player_index)?;
-        player_index.increment();
+#[cfg(feature = "save")]
+fn record(path: &str, log: &EventLog) -> Result<()> {
+    log.write_jsonl(std::io::BufWriter::new(std::fs::File::create(path)?))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "save"))]
+fn record(_path: &str, _log: &EventLog) -> Result<()> {
+    Err("Recording needs the `save` feature".into())
+}
+
+fn play_tui(game: FishGame, turns: TurnOrder) -> Result<FishGame> {
+    let mut tui = Tui::new()?;
+    let game = play_game(game, turns, &mut tui)?;
+    tui.finish(&game)?;
+    Ok(game)
+}
+
+fn play_game<U: Ui>(mut game: FishGame, mut turns: TurnOrder, ui: &mut U) -> Result<FishGame> {
+    while !game.has_empty_hand() && !game.river.is_empty() {
+        ui.show(&game)?;
+        game.turn(&turns, ui)?;
+        if cfg!(debug_assertions) {
+            Audit::default().check(&game.census())?;
+        }
+        turns.advance();
     }
 
-    println!("{}", &game);
+    game.finish(ui);
+    ui.show(&game)?;
+
+    Ok(game)
+}
 
+fn winner(game: FishGame) -> Result<Player> {
     match game
         .players
         .into_iter()
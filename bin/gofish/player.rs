@@ -4,6 +4,7 @@ use std::io;
 pub use PlayerType::*;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Player {
     pub name: String,
     pub hand: Hand,
@@ -52,10 +53,11 @@ impl Player {
             .cards()
             .enumerate()
             .filter(|(_, card)| card.value == value.into())
-            .map(|(i, card)| (i, card.clone()))
+            .map(|(i, card)| (i, *card))
             .collect()
     }
 
+    #[allow(dead_code)]
     pub fn say<W: Write, D: fmt::Display>(&self, w: &mut W, d: D) -> io::Result<()> {
         write!(w, "{}: \"{}\"", self.name, d)
     }
@@ -76,30 +78,8 @@ impl Give for Player {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlayerType {
     Human,
     Computer,
 }
-
-pub struct PlayerIndex {
-    pub current: usize,
-    pub next: usize,
-    pub count: usize,
-}
-
-impl PlayerIndex {
-    pub fn new(count: usize) -> Self {
-        PlayerIndex {
-            current: 0,
-            next: 1,
-            count,
-        }
-    }
-}
-
-impl PlayerIndex {
-    pub fn increment(&mut self) {
-        self.current = self.next;
-        self.next = (self.next + 1) % self.count;
-    }
-}
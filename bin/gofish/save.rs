@@ -0,0 +1,103 @@
+//! Saving a game in progress and resuming it later
+
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+/// The version written into new saves
+///
+/// Bump this when the format changes, and keep `load` reading the old ones.
+pub const SAVE_VERSION: u64 = 2;
+
+/// Where `save` writes when no file is given
+pub const DEFAULT_SAVE: &str = "gofish.save.json";
+
+#[derive(Serialize)]
+struct SaveRef<'a> {
+    version: u64,
+    game: &'a FishGame,
+    turns: &'a TurnOrder,
+}
+
+#[derive(Deserialize)]
+struct SaveV2 {
+    game: FishGame,
+    turns: TurnOrder,
+}
+
+/// Version 1 kept whose turn it was as a current and next seat
+#[derive(Deserialize)]
+struct SaveV1 {
+    game: FishGame,
+    index: PlayerIndexV1,
+}
+
+#[derive(Deserialize)]
+struct PlayerIndexV1 {
+    current: usize,
+    count: usize,
+}
+
+impl From<PlayerIndexV1> for TurnOrder {
+    fn from(index: PlayerIndexV1) -> Self {
+        TurnOrder::left_of_dealer(index.count, index.current, 0)
+    }
+}
+
+/// Write the whole game and whose turn it is
+pub fn save<P: AsRef<Path>>(path: P, game: &FishGame, turns: &TurnOrder) -> Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    let save = SaveRef {
+        version: SAVE_VERSION,
+        game,
+        turns,
+    };
+    serde_json::to_writer_pretty(file, &save)?;
+    Ok(())
+}
+
+/// Read a game written by `save`, from this or any earlier version
+pub fn load<P: AsRef<Path>>(path: P) -> Result<(FishGame, TurnOrder)> {
+    let json = fs::read_to_string(path)?;
+    let Version { version } = serde_json::from_str(&json)?;
+
+    let (game, turns) = match version {
+        Some(1) => {
+            let save: SaveV1 = serde_json::from_str(&json)?;
+            (save.game, save.index.into())
+        }
+        Some(2) => {
+            let save: SaveV2 = serde_json::from_str(&json)?;
+            (save.game, save.turns)
+        }
+        version => return Err(Box::new(SaveError::UnknownVersion(version))),
+    };
+
+    // The river and the players hold the whole deck between them, each card once
+    Audit::default().check(&game.census())?;
+    Ok((game, turns))
+}
+
+/// Just enough of a save to know how to read the rest
+#[derive(Deserialize)]
+struct Version {
+    version: Option<u64>,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    UnknownVersion(Option<u64>),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::UnknownVersion(Some(v)) => write!(f, "Unknown save version {}", v),
+            SaveError::UnknownVersion(None) => write!(f, "Not a gofish save"),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
@@ -0,0 +1,153 @@
+use super::*;
+use rand_pcg::Pcg64Mcg;
+
+/// Plays the human's part without a terminal: always asks for the first card
+/// in hand and always fishes from the top of the river
+#[derive(Default)]
+struct Scripted {
+    events: Vec<String>,
+    shown: usize,
+}
+
+impl Ui for Scripted {
+    fn ask_value(&mut self, game: &FishGame, turns: &TurnOrder) -> Result<Value> {
+        let card = game.players[turns.current()].hand.cards().next().expect("empty hand");
+        Ok(card.value)
+    }
+
+    fn ask_index(&mut self, _game: &FishGame) -> Result<usize> {
+        Ok(0)
+    }
+
+    fn event(&mut self, message: &str) {
+        self.events.push(message.into());
+    }
+
+    fn show(&mut self, _game: &FishGame) -> Result<()> {
+        self.shown += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn headless_game() -> Result<()> {
+    let mut ui = Scripted::default();
+    let game = play_game(FishGame::new(3)?, TurnOrder::new(3), &mut ui)?;
+
+    assert!(game.has_empty_hand() || game.river.is_empty());
+    assert!(!ui.events.is_empty());
+    assert!(ui.shown > 1);
+
+    // Every card is still somewhere on the table
+    let held: usize = game.players.iter()
+        .map(|p| p.hand.len() + p.paired.len())
+        .sum();
+    assert_eq!(held + game.river.len(), 52);
+
+    winner(game)?;
+
+    Ok(())
+}
+
+#[test]
+fn stacked_turns() -> Result<()> {
+    let cards = |s: &str| parse_cards(s).expect("cards");
+    let river = FishGame::pattern(2).stack(Deck::default(), &[
+        (Destination::Seat(0), cards("7S 2S 3S 4S 5S")),
+        (Destination::Seat(1), cards("7H 8H 9H TH JH")),
+        (Destination::Stock, cards("2H")),
+    ])?;
+    let mut game = FishGame::with_river(2, river, Pcg64Mcg::new(0))?;
+    let mut turns = TurnOrder::new(2);
+    let mut ui = Scripted::default();
+
+    // Human0 asks for sevens and gets one
+    game.turn(&turns, &mut ui)?;
+    let events = &game.log.events[3..];
+    assert_eq!(events[0], Event::Asked { player: 0, target: 1, value: Value::Seven });
+    assert_eq!(events[1], Event::Gave { from: 1, to: 0, cards: cards("7H") });
+    assert_eq!(events[2], Event::PairsDiscarded { player: 0, cards: cards("7S 7H") });
+
+    // Then asks for twos, and fishes one from the top of the river
+    turns.extra_turn();
+    turns.advance();
+    game.turn(&turns, &mut ui)?;
+    let events = &game.log.events[6..];
+    assert_eq!(events[0], Event::Asked { player: 0, target: 1, value: Value::Two });
+    assert_eq!(events[1], Event::WentFishing { player: 0, card: cards("2H")[0] });
+    assert_eq!(events[2], Event::PairsDiscarded { player: 0, cards: cards("2S 2H") });
+    assert_eq!(game.players[0].hand, "3S 4S 5S".parse()?);
+
+    Ok(())
+}
+
+#[cfg(feature = "save")]
+#[test]
+fn save_and_resume() -> Result<()> {
+    let mut game = FishGame::new(2)?;
+    let mut turns = TurnOrder::new(2);
+    let mut ui = Scripted::default();
+    game.turn(&turns, &mut ui)?;
+    turns.advance();
+
+    let path = std::env::temp_dir().join(format!("gofish-{}.json", std::process::id()));
+    save::save(&path, &game, &turns)?;
+    let (loaded, loaded_turns) = save::load(&path)?;
+
+    // A save with a card missing doesn't load
+    let mut json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    json["game"]["river"].as_array_mut().expect("river").pop();
+    std::fs::write(&path, json.to_string())?;
+    assert!(save::load(&path).is_err());
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(loaded.river, game.river);
+    assert_eq!(loaded_turns, turns);
+    for (a, b) in loaded.players.iter().zip(game.players.iter()) {
+        assert_eq!(a.hand, b.hand);
+        assert_eq!(a.paired, b.paired);
+        assert_eq!(a.player_type, b.player_type);
+    }
+
+    // Both copies make the same computer moves from here on
+    let mut a = play_game(game, turns, &mut Scripted::default())?;
+    let mut b = play_game(loaded, loaded_turns, &mut Scripted::default())?;
+    assert_eq!(a.river.len(), b.river.len());
+    assert_eq!(a.players.remove(1).hand, b.players.remove(1).hand);
+
+    Ok(())
+}
+
+#[cfg(feature = "save")]
+#[test]
+fn load_version_1_save() -> Result<()> {
+    let v1 = r#"{
+        "version": 1,
+        "game": {
+            "river": [
+                "6S", "7S", "8S", "9S", "TS", "JS", "QS", "KS", "AC", "2C", "3C", "4C", "5C", "6C",
+                "7C", "8C", "9C", "TC", "JC", "QC", "KC", "6H", "7H", "8H", "9H", "TH", "JH", "QH",
+                "KH", "AD", "2D", "3D", "4D", "5D", "6D", "7D", "8D", "9D", "TD", "JD", "QD", "KD"
+            ],
+            "players": [
+                { "name": "Human0", "hand": ["AS", "2S", "3S", "4S", "5S"], "paired": [], "player_type": "Human" },
+                { "name": "Computer1", "hand": ["AH", "2H", "3H", "4H", "5H"], "paired": [], "player_type": "Computer" }
+            ],
+            "rng": { "state": 1 }
+        },
+        "index": { "current": 1, "count": 2 }
+    }"#;
+
+    let path = std::env::temp_dir().join(format!("gofish-v1-{}.json", std::process::id()));
+    std::fs::write(&path, v1)?;
+    let loaded = save::load(&path);
+    std::fs::remove_file(&path)?;
+    let (game, turns) = loaded?;
+
+    assert_eq!(game.players[0].hand, "AS 2S 3S 4S 5S".parse()?);
+    assert_eq!(game.players[1].hand, "AH 2H 3H 4H 5H".parse()?);
+    assert_eq!(game.river.len(), 42);
+    assert_eq!((turns.seats(), turns.current(), turns.next()), (2, 1, 0));
+
+    Ok(())
+}
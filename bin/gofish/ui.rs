@@ -0,0 +1,234 @@
+use super::*;
+use cards::tui::{Choice, Seat, Terminal, View};
+use std::io;
+
+/// Everything the game needs from whoever is at the keyboard
+pub trait Ui {
+    /// Ask the human which value to ask the next player for
+    fn ask_value(&mut self, game: &FishGame, turns: &TurnOrder) -> Result<Value>;
+
+    /// Ask the human which card to take from the river
+    fn ask_index(&mut self, game: &FishGame) -> Result<usize>;
+
+    /// Report something that happened
+    fn event(&mut self, message: &str);
+
+    /// Show the whole table
+    fn show(&mut self, game: &FishGame) -> Result<()>;
+
+    /// Give the human a moment to follow what the computer did
+    fn pause(&mut self) {}
+}
+
+/// The line-based interface on stdin and stdout
+pub struct Console;
+
+impl Ui for Console {
+    fn ask_value(&mut self, game: &FishGame, turns: &TurnOrder) -> Result<Value> {
+        Ok(human_ask_value(&mut stdout(), game, turns))
+    }
+
+    fn ask_index(&mut self, game: &FishGame) -> Result<usize> {
+        Ok(ask_user_index(&mut stdout(), game.river.len()))
+    }
+
+    fn event(&mut self, message: &str) {
+        println!("{}", message);
+    }
+
+    fn show(&mut self, game: &FishGame) -> Result<()> {
+        println!("{}", game);
+        Ok(())
+    }
+
+    fn pause(&mut self) {
+        sleep(2);
+    }
+}
+
+fn ask_user_index<W: Write>(w: &mut W, limit: usize) -> usize {
+    write!(w, "Go fish! [0-{}]: ", limit - 1).expect("write");
+    w.flush().expect("write");
+
+    let mut input = String::new();
+    stdin().read_line(&mut input).expect("stdin");
+
+    match input.trim().parse::<usize>() {
+        Ok(u) => {
+            if u < limit {
+                u
+            } else {
+                eprintln!("Index out of bounds!");
+                ask_user_index(w, limit)
+            }
+        }
+        Err(_) => ask_user_index(w, limit),
+    }
+}
+
+fn human_ask_value<W: Write>(w: &mut W, game: &FishGame, turns: &TurnOrder) -> Value {
+    let player = &game.players[turns.current()];
+    let next = &game.players[turns.next()];
+    write!(w, "{}: Ask {} for a card value: ", player.name, next.name).expect("write");
+    w.flush().expect("write");
+
+    let mut input = String::new();
+    stdin().read_line(&mut input).expect("stdin");
+
+    if save_command(w, input.trim(), game, turns) {
+        return human_ask_value(w, game, turns);
+    }
+
+    match Value::from_phrase(input.trim()) {
+        Some(v) => {
+            if player.has_value(v) {
+                v
+            } else {
+                writeln!(w, "You don't have that card!").expect("write");
+                human_ask_value(w, game, turns)
+            }
+        }
+        None => {
+            writeln!(w, "Invalid card value!").expect("write");
+            human_ask_value(w, game, turns)
+        }
+    }
+}
+
+/// Handle `save [file]` typed at the prompt, returning whether it was one
+fn save_command<W: Write>(w: &mut W, input: &str, game: &FishGame, turns: &TurnOrder) -> bool {
+    let mut words = input.split_whitespace();
+    if words.next() != Some("save") {
+        return false;
+    }
+
+    writeln!(w, "{}", save_game(words.next(), game, turns)).expect("write");
+    true
+}
+
+/// Save to the file, or the default one, and say how it went
+#[cfg(feature = "save")]
+fn save_game(path: Option<&str>, game: &FishGame, turns: &TurnOrder) -> String {
+    let path = path.unwrap_or(save::DEFAULT_SAVE);
+    match save::save(path, game, turns) {
+        Ok(()) => format!("Saved to {}", path),
+        Err(e) => format!("Couldn't save: {}", e),
+    }
+}
+
+#[cfg(not(feature = "save"))]
+fn save_game(_path: Option<&str>, _game: &FishGame, _turns: &TurnOrder) -> String {
+    String::from("Saving needs the `save` feature")
+}
+
+/// The full-screen interface
+pub struct Tui {
+    terminal: Terminal,
+    log: Vec<String>,
+    shown: View,
+}
+
+impl Tui {
+    pub fn new() -> io::Result<Self> {
+        Ok(Tui {
+            terminal: Terminal::new()?,
+            log: Vec::new(),
+            shown: View::default(),
+        })
+    }
+
+    /// Wait for a key press before leaving the screen
+    pub fn finish(&mut self, game: &FishGame) -> Result<()> {
+        let view = self.view(game, "Game over! Press any key to exit");
+        self.terminal.draw(&view)?;
+        self.terminal.key()?;
+        Ok(())
+    }
+
+    fn view(&self, game: &FishGame, status: &str) -> View {
+        let mut view = View::new("Go Fish");
+
+        for player in game.players.iter() {
+            let (faces, spread) = match player.player_type {
+                Human => (player.hand.cards().map(|c| Face::Up(*c)).collect(), Spread::Row),
+                Computer => (vec![Face::Down; player.hand.len()], Spread::Fan),
+            };
+            let label = format!("{} (paired {})", player.name, player.paired.len());
+            view.seats.push(Seat::new(&label, faces, spread));
+        }
+
+        let river = format!("River ({})", game.river.len());
+        view.seats.push(Seat::new(&river, vec![Face::Down; game.river.len()], Spread::Stack));
+
+        view.log = self.log.clone();
+        view.status = status.into();
+        view
+    }
+
+    fn select(&mut self, mut view: View, seat: usize) -> Result<usize> {
+        match self.terminal.select(&mut view, seat)? {
+            Some(index) => Ok(index),
+            None => Err(Box::new(Quit)),
+        }
+    }
+}
+
+impl Ui for Tui {
+    fn ask_value(&mut self, game: &FishGame, turns: &TurnOrder) -> Result<Value> {
+        let status = format!(
+            "Ask {} for a card value: ←/→ to choose, Enter to ask, s to save, q to quit",
+            game.players[turns.next()].name,
+        );
+
+        loop {
+            let mut view = self.view(game, &status);
+            match self.terminal.choose(&mut view, turns.current(), &['s'])? {
+                Choice::Card(card) => {
+                    return Ok(game.players[turns.current()].hand.cards().nth(card).expect("selected card").value);
+                }
+                Choice::Key(_) => {
+                    let message = save_game(None, game, turns);
+                    self.log.push(message);
+                }
+                Choice::Quit => return Err(Box::new(Quit)),
+            }
+        }
+    }
+
+    fn ask_index(&mut self, game: &FishGame) -> Result<usize> {
+        let view = self.view(game, "Go fish! ←/→ to choose, Enter to take, q to quit");
+        self.select(view, game.players.len())
+    }
+
+    fn event(&mut self, message: &str) {
+        self.log.push(message.into());
+        self.shown.log = self.log.clone();
+        let _ = self.terminal.draw(&self.shown);
+    }
+
+    fn show(&mut self, game: &FishGame) -> Result<()> {
+        self.shown = self.view(game, "");
+        self.terminal.draw(&self.shown)?;
+        Ok(())
+    }
+
+    fn pause(&mut self) {
+        sleep(1);
+    }
+}
+
+fn sleep(secs: u64) {
+    std::thread::sleep(std::time::Duration::from_secs(secs))
+}
+
+/// The human quit in the middle of the game
+#[derive(Debug)]
+pub struct Quit;
+
+impl fmt::Display for Quit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for Quit {}
@@ -0,0 +1,78 @@
+use super::*;
+use rand::Rng;
+
+/// Hands the bots will play before the flop
+const PLAYABLE: &str = "22+, A2s+, K9s+, Q9s+, J9s+, T9s, 98s, 87s, ATo+, KTo+, QJo";
+
+/// A computer player that weighs its chance of winning against the price
+pub struct Bot {
+    rng: Pcg64Mcg,
+    playable: Range,
+    /// Random boards dealt to estimate equity
+    pub trials: usize,
+}
+
+impl Bot {
+    pub fn new(rng: Pcg64Mcg) -> Self {
+        Bot {
+            rng,
+            playable: PLAYABLE.parse().expect("bot range"),
+            trials: 200,
+        }
+    }
+
+    /// The share of the pot the seat wins on average against random hands
+    /// for everyone still in
+    fn equity(&mut self, game: &Holdem, seat: usize) -> f64 {
+        let hole: Vec<Card> = game.hole(seat).cards().copied().collect();
+        let board: Vec<Card> = game.board().cards().copied().collect();
+        let opponents = game.chips.iter().enumerate().filter(|(i, c)| *i != seat && c.in_hand()).count();
+        let mut stub: Vec<Card> = Deck::default()
+            .cards()
+            .filter(|c| !hole.contains(c) && !board.contains(c))
+            .copied()
+            .collect();
+        let needed = 2 * opponents + 5 - board.len();
+
+        let mut total = 0.0;
+        for _ in 0..self.trials {
+            for i in 0..needed {
+                let j = self.rng.gen_range(i, stub.len());
+                stub.swap(i, j);
+            }
+            let (holes, rest) = stub[..needed].split_at(2 * opponents);
+            let full: Vec<Card> = board.iter().chain(rest).copied().collect();
+            let strengths: Vec<Option<Strength>> = std::iter::once(&hole[..])
+                .chain(holes.chunks(2))
+                .map(|two| evaluate(&[two, &full[..]].concat()))
+                .collect();
+            total += pot_shares(&strengths)[0];
+        }
+        total / self.trials as f64
+    }
+}
+
+impl Player for Bot {
+    fn act(&mut self, game: &Holdem, seat: usize) -> Result<Action> {
+        let options = game.options().ok_or("Nobody to act")?;
+        if game.street == Street::Preflop && options.call > 0 && self.playable.weight_of(game.hole(seat)) == 0.0 {
+            return Ok(Action::Fold);
+        }
+
+        let equity = self.equity(game, seat);
+        let pot: u64 = game.chips.iter().map(|c| c.committed).sum();
+        let price = options.call as f64 / (pot + options.call) as f64;
+
+        if let Some((least, most)) = options.raise {
+            if equity > 0.7 || (equity > 0.5 && self.rng.gen_bool(0.3)) {
+                let to = (game.current_bet + pot).clamp(least, most);
+                return Ok(Action::RaiseTo(to));
+            }
+        }
+        Ok(match options.call {
+            0 => Action::Check,
+            _ if equity >= price => Action::Call,
+            _ => Action::Fold,
+        })
+    }
+}
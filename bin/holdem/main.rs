@@ -0,0 +1,146 @@
+use cards::*;
+
+use rand::SeedableRng;
+use rand_pcg::Pcg64Mcg;
+use std::error::Error;
+use std::io::{stdin, stdout, Write};
+
+mod bot;
+
+#[cfg(test)]
+mod test;
+
+use bot::*;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+const STARTING_CHIPS: u64 = 1000;
+const BLINDS: Blinds = Blinds { small: 5, big: 10 };
+
+/// Whoever decides what a seat does
+pub trait Player {
+    fn act(&mut self, game: &Holdem, seat: usize) -> Result<Action>;
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let betting = if args.iter().any(|arg| arg == "--limit") { Betting::Limit } else { Betting::NoLimit };
+    let bots = args.iter()
+        .position(|arg| arg == "--bots")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|n| n.parse::<usize>().ok())
+        .unwrap_or(3)
+        .clamp(1, 9);
+
+    let mut rng = Pcg64Mcg::from_rng(rand::thread_rng()).expect("seed rng");
+    let mut names = vec![String::from("You")];
+    let mut players: Vec<Box<dyn Player>> = vec![Box::new(Console)];
+    for n in 1..=bots {
+        names.push(format!("Bot{}", n));
+        players.push(Box::new(Bot::new(Pcg64Mcg::from_rng(&mut rng).expect("seed rng"))));
+    }
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    let mut game = Holdem::new(betting, BLINDS, &names, STARTING_CHIPS);
+
+    while game.chips[0].stack > 0 && game.players_left() > 1 {
+        if let Err(e) = play_hand(&mut game, &mut players, &mut rng, &mut stdout()) {
+            eprintln!("{}", e);
+            break;
+        }
+    }
+
+    if game.players_left() == 1 {
+        let winner = game.chips.iter().position(|c| c.stack > 0).expect("a winner");
+        println!("{} won every chip!", game.name(winner));
+    }
+
+    Ok(())
+}
+
+/// Shuffle, deal and play one hand, reporting it to `w`
+fn play_hand<W: Write>(game: &mut Holdem, players: &mut [Box<dyn Player>], rng: &mut Pcg64Mcg, w: &mut W) -> Result<()> {
+    let mut deck = Deck::default();
+    deck.mix_with(&[Mix::Uniform], rng);
+    game.next_hand(deck)?;
+    writeln!(w, "\n{} has the button", game.name(game.button()))?;
+
+    let mut board = 0;
+    while let Some(seat) = game.to_act {
+        let action = players[seat].act(game, seat)?;
+        match game.act(action) {
+            Ok(()) => writeln!(w, "{} {}", game.name(seat), action)?,
+            Err(e) => writeln!(w, "{}", e)?,
+        }
+        if game.board().len() != board {
+            board = game.board().len();
+            writeln!(w, "Board: {:?}", game.board())?;
+        }
+    }
+    if game.board().len() != board {
+        writeln!(w, "Board: {:?}", game.board())?;
+    }
+
+    let shown = game.chips.iter().filter(|c| c.in_hand()).count() > 1;
+    for (i, chips) in game.chips.iter().enumerate() {
+        if shown && chips.in_hand() {
+            let strength = game.strength(i).map_or(String::new(), |s| s.to_string());
+            writeln!(w, "{} shows {:?}: {}", game.name(i), game.hole(i), strength)?;
+        }
+        if chips.won > 0 {
+            writeln!(w, "{} wins {}", game.name(i), chips.won)?;
+        }
+    }
+    let stacks: Vec<String> = game.chips.iter().enumerate().map(|(i, c)| format!("{} {}", game.name(i), c.stack)).collect();
+    writeln!(w, "Chips: {}", stacks.join(", "))?;
+
+    Ok(())
+}
+
+/// The human at the keyboard
+pub struct Console;
+
+impl Player for Console {
+    fn act(&mut self, game: &Holdem, seat: usize) -> Result<Action> {
+        let options = game.options().ok_or("Nobody to act")?;
+        let pot: u64 = game.chips.iter().map(|c| c.committed).sum();
+        println!(
+            "Your cards: {:?}  Board: {:?}  Pot: {}  Chips: {}",
+            game.hole(seat), game.board(), pot, game.chips[seat].stack,
+        );
+
+        let call = match options.call {
+            0 => String::from("[c]heck"),
+            n => format!("[c]all {}", n),
+        };
+        let raise = match options.raise {
+            Some((least, most)) if least == most => format!(", [r]aise to {}", least),
+            Some((least, most)) => format!(", [r]aise <to> ({}-{})", least, most),
+            None => String::new(),
+        };
+
+        loop {
+            print!("[f]old, {}{}, [a]ll in, [q]uit: ", call, raise);
+            stdout().flush()?;
+            let mut input = String::new();
+            stdin().read_line(&mut input)?;
+            let words: Vec<&str> = input.split_whitespace().collect();
+
+            let action = match words.as_slice() {
+                ["f"] | ["fold"] => Action::Fold,
+                ["c"] | ["k"] | ["call"] | ["check"] => Action::Call,
+                ["a"] | ["all"] | ["allin"] => Action::AllIn,
+                ["r"] | ["raise"] => match options.raise {
+                    Some((least, _)) => Action::RaiseTo(least),
+                    None => continue,
+                },
+                ["r", to] | ["raise", to] => match to.parse() {
+                    Ok(to) => Action::RaiseTo(to),
+                    Err(_) => continue,
+                },
+                ["q"] | ["quit"] => return Err("Thanks for playing".into()),
+                _ => continue,
+            };
+            return Ok(action);
+        }
+    }
+}
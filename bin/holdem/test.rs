@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn bots_play_it_out() -> Result<()> {
+    for (betting, seed) in [(Betting::NoLimit, 1), (Betting::Limit, 2)].iter() {
+        let mut rng = Pcg64Mcg::seed_from_u64(*seed);
+        let mut players: Vec<Box<dyn Player>> = (0..4)
+            .map(|n| {
+                let mut bot = Bot::new(Pcg64Mcg::seed_from_u64(seed * 10 + n));
+                bot.trials = 50;
+                Box::new(bot) as Box<dyn Player>
+            })
+            .collect();
+        let mut game = Holdem::new(*betting, BLINDS, &["Bot0", "Bot1", "Bot2", "Bot3"], 200);
+
+        let mut report = Vec::new();
+        for _ in 0..100 {
+            if game.players_left() < 2 {
+                break;
+            }
+            play_hand(&mut game, &mut players, &mut rng, &mut report)?;
+
+            // No chips or cards go missing
+            assert_eq!(game.total_chips(), 800);
+            assert!(Audit::default().check(&game.table.census()).is_ok());
+        }
+
+        let report = String::from_utf8(report)?;
+        assert!(report.contains(" wins "));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use cards::*;
+use cards::tui::{Key, Seat, Terminal, View};
+
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+const USAGE: &str = "Usage: replay <events.jsonl> [--print]";
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let path = match args.iter().find(|arg| !arg.starts_with("--")) {
+        Some(path) => path,
+        None => {
+            eprintln!("{}", USAGE);
+            return Ok(());
+        }
+    };
+
+    let log = EventLog::read_jsonl(BufReader::new(File::open(path)?))?;
+    let mut replay = Replay::new(log);
+
+    if args.iter().any(|arg| arg == "--print") {
+        print(&mut replay);
+        Ok(())
+    } else {
+        browse(&mut replay)
+    }
+}
+
+/// Print every event, then the table at the end
+fn print(replay: &mut Replay) {
+    while replay.forward() {
+        if let Some(event) = replay.last_event() {
+            println!("{}", event);
+        }
+    }
+
+    let snapshot = replay.snapshot();
+    for (n, name) in snapshot.players.iter().enumerate() {
+        println!("{}: {:?} {:?}", name, snapshot.hands[n], snapshot.piles[n]);
+    }
+}
+
+/// Step through the game with the arrow keys
+fn browse(replay: &mut Replay) -> Result<()> {
+    let mut terminal = Terminal::new()?;
+
+    loop {
+        terminal.draw(&view(replay))?;
+
+        match terminal.key()? {
+            Key::Right | Key::Enter => {
+                replay.forward();
+            }
+            Key::Left => {
+                replay.back();
+            }
+            Key::Up => replay.seek(0),
+            Key::Down => replay.seek(replay.log.len()),
+            Key::Quit => break,
+            Key::Char(_) => (),
+        }
+    }
+
+    Ok(())
+}
+
+fn view(replay: &Replay) -> View {
+    let snapshot = replay.snapshot();
+    let mut view = View::new("Replay");
+
+    for (n, name) in snapshot.players.iter().enumerate() {
+        let faces = snapshot.hands[n].cards().map(|c| Face::Up(*c)).collect();
+        view.seats.push(Seat::new(name, faces, Spread::Fan));
+
+        if !snapshot.piles[n].is_empty() {
+            let label = format!("{} pile ({})", name, snapshot.piles[n].len());
+            let faces = snapshot.piles[n].cards().map(|c| Face::Up(*c)).collect();
+            view.seats.push(Seat::new(&label, faces, Spread::Stack));
+        }
+    }
+
+    view.log = replay.log.events[..replay.position()]
+        .iter()
+        .map(|event| event.to_string())
+        .collect();
+    view.status = format!(
+        "Event {}/{}, stock {}: ←/→ to step, ↑/↓ for start/end, q to quit",
+        replay.position(),
+        replay.log.len(),
+        snapshot.stock,
+    );
+
+    view
+}
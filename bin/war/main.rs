@@ -1,22 +1,66 @@
 use cards::*;
+use cards::tui::{Key, Seat, Terminal, View};
 
 #[cfg(test)]
 mod test;
 
 fn main() -> Result<(), Box<dyn std::error::Error>>{
-    play_game()?;
+    let args: Vec<String> = std::env::args().collect();
+    let mut log = EventLog::new();
+
+    if args.iter().any(|arg| arg == "--tui") {
+        play_tui(&mut log)?;
+    } else {
+        play_game(&mut log)?;
+    }
+
+    if let Some(i) = args.iter().position(|arg| arg == "--record") {
+        let path = args.get(i + 1).ok_or("--record needs a file")?;
+        record(path, &log)?;
+    }
+
     Ok(())
 }
 
-fn play_game() -> DealResult<(usize, usize, usize, usize)> {
-    let mut game = Game::new(2, 26)?;
+#[cfg(feature = "save")]
+fn record(path: &str, log: &EventLog) -> std::io::Result<()> {
+    log.write_jsonl(std::io::BufWriter::new(std::fs::File::create(path)?))
+}
+
+#[cfg(not(feature = "save"))]
+fn record(_path: &str, _log: &EventLog) -> std::io::Result<()> {
+    Err(std::io::Error::other("Recording needs the `save` feature"))
+}
+
+/// Deal a new game and record the deal
+fn deal(log: &mut EventLog) -> DealResult<Game> {
+    deal_deck(Deck::new(), log)
+}
+
+/// Deal the whole deck, one card at a time
+fn deal_deck(deck: Deck, log: &mut EventLog) -> DealResult<Game> {
+    let game = Game::with_pattern(deck, &DealPattern::round_robin(2, 26))?;
+
+    log.push(Event::Started {
+        players: vec![String::from("Player 0"), String::from("Player 1")],
+        stock: 52,
+    });
+    for (player, hand) in game.hands.iter().enumerate() {
+        log.push(Event::Dealt { player, cards: hand.cards().copied().collect() });
+    }
+
+    Ok(game)
+}
+
+fn play_game(log: &mut EventLog) -> DealResult<(usize, usize, usize, usize)> {
+    let mut game = deal(log)?;
     let mut rounds = 0;
     let mut war_count = 0;
 
     while !game.has_empty_hand() {
-        round(&mut game, &mut war_count)?;
+        round(&mut game, &mut war_count, log)?;
         rounds += 1;
-        if rounds == std::usize::MAX  { 
+        if rounds == usize::MAX  { 
             eprint!("ENDLESS GAME!");
             dbg!(&game);
             break;
@@ -28,43 +72,87 @@ fn play_game() -> DealResult<(usize, usize, usize, usize)> {
         .expect("Empty hands!");
 
     let win_len = win_hand.len();
+    log.push(Event::GameOver { winner: Some(player) });
 
     println!("Player {} wins with {} wars in {} rounds!", player, war_count, rounds);
 
-    // Test that all cards are unique
-    assert!(game.hands[0].are_unique());
-    assert!(game.hands[1].are_unique());
+    // Test that every card is still in play exactly once
+    let mut census = Census::new();
+    census.count("deck", &game.deck)
+        .count("Player 0", &game.hands[0])
+        .count("Player 1", &game.hands[1]);
+    if let Err(e) = Audit::default().check(&census) {
+        panic!("{}", e);
+    }
 
     Ok((player, win_len, rounds, war_count))
 }
 
-fn round(game: &mut Game, war_count: &mut usize) -> DealResult<()> {
+fn play_tui(log: &mut EventLog) -> Result<(), Box<dyn std::error::Error>> {
+    let mut terminal = Terminal::new()?;
+    let mut game = deal(log)?;
+    let mut rounds = 0;
+    let mut war_count = 0;
+
+    while !game.has_empty_hand() {
+        let battle: Vec<Face> = game.hands.iter()
+            .filter_map(|hand| hand.cards().next())
+            .map(|card| Face::Up(*card))
+            .collect();
+
+        round(&mut game, &mut war_count, log)?;
+        rounds += 1;
+
+        let mut view = View::new("War");
+        for (player, hand) in game.hands.iter().enumerate() {
+            let label = format!("Player {} ({})", player, hand.len());
+            view.seats.push(Seat::new(&label, vec![Face::Down; hand.len()], Spread::Stack));
+        }
+        view.seats.push(Seat::new("Battle", battle, Spread::Row));
+        view.log = log.events.iter().skip(3).map(|event| event.to_string()).collect();
+        view.status = format!("Round {}, {} wars: Space to play, q to quit", rounds, war_count);
+
+        terminal.draw(&view)?;
+        if terminal.key()? == Key::Quit {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn round(game: &mut Game, war_count: &mut usize, log: &mut EventLog) -> DealResult<()> {
     let card0 = game.hands[0].give(0)?;
     let card1 = game.hands[1].give(0)?;
 
     if card0.cmp_value() == card1.cmp_value() {
         *war_count += 1;
-        war(game, &mut vec![card0, card1])?;
+        log.push(Event::WarStarted { cards: vec![card0, card1] });
+        war(game, &mut vec![card0, card1], log)?;
     } else if card0 > card1 {
         game.hands[0].take(card0);
         game.hands[0].take(card1);
+        log.push(Event::RoundWon { player: 0, cards: vec![card0, card1] });
     } else {
         game.hands[1].take(card1);
         game.hands[1].take(card0);
+        log.push(Event::RoundWon { player: 1, cards: vec![card1, card0] });
     }
 
     Ok(())
 }
 
-fn war(game: &mut Game, war_cards: &mut Vec<Card>) -> DealResult<()> {
+fn war(game: &mut Game, war_cards: &mut Vec<Card>, log: &mut EventLog) -> DealResult<()> {
     let len0 = game.hands[0].len();
     let len1 = game.hands[1].len();
 
     if len0 < 4 {
         take_all(&mut game.hands[1], war_cards);
+        log.push(Event::RoundWon { player: 1, cards: war_cards.clone() });
         return Ok(());
     } else if len1 < 4 {
         take_all(&mut game.hands[0], war_cards);
+        log.push(Event::RoundWon { player: 0, cards: war_cards.clone() });
         return Ok(());
     }
     
@@ -82,19 +170,25 @@ fn war(game: &mut Game, war_cards: &mut Vec<Card>) -> DealResult<()> {
         war_cards.push(*card);
     }
 
-    if war_cards[5].cmp_value() == war_cards[9].cmp_value() {
-        war(game, war_cards)?;
-    } else if war_cards[5] > war_cards[9] {
+    // The last card each player put down is face up
+    let (up0, up1) = (war_cards[war_cards.len() - 5], war_cards[war_cards.len() - 1]);
+
+    if up0.cmp_value() == up1.cmp_value() {
+        log.push(Event::WarStarted { cards: vec![up0, up1] });
+        war(game, war_cards, log)?;
+    } else if up0 > up1 {
         take_all(&mut game.hands[0], war_cards); 
+        log.push(Event::RoundWon { player: 0, cards: war_cards.clone() });
     } else {
         take_all(&mut game.hands[1], war_cards); 
+        log.push(Event::RoundWon { player: 1, cards: war_cards.clone() });
     }
 
     Ok(())
 }
 
-fn take_all(hand: &mut Hand, cards: &mut Vec<Card>) {
-    for card in cards.into_iter() {
+fn take_all(hand: &mut Hand, cards: &[Card]) {
+    for card in cards.iter() {
         hand.take(*card);
     }
 }
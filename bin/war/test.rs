@@ -14,7 +14,7 @@ fn test_war() -> Result<(), Box<dyn std::error::Error>> {
 
     // Play the game 10000 times
     for _i in 0..ITERATIONS {
-        let (winner, win_len, rounds, war_count) = play_game()?;
+        let (winner, win_len, rounds, war_count) = play_game(&mut EventLog::new())?;
         // Winner must have all 52 cards
         assert_eq!(win_len, 52);
 
@@ -72,7 +72,7 @@ fn test_war() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 fn get_closest(map: &HashMap<usize, usize>, val: usize) -> usize {
-    let mut find_val = val.clone();
+    let mut find_val = val;
 
     if let Some(value) = map.get(&find_val) {
         *value
@@ -81,3 +81,40 @@ fn get_closest(map: &HashMap<usize, usize>, val: usize) -> usize {
         get_closest(map, find_val)
     }
 }
+
+#[test]
+fn replay_war() -> Result<(), Box<dyn std::error::Error>> {
+    let mut log = EventLog::new();
+    let (winner, _, _, _) = play_game(&mut log)?;
+
+    let mut replay = Replay::new(log);
+    replay.seek(usize::MAX);
+    assert_eq!(replay.snapshot().winner, Some(winner));
+    assert_eq!(replay.snapshot().hands[winner].len(), 52);
+    assert_eq!(replay.snapshot().stock, 0);
+
+    Ok(())
+}
+
+#[test]
+fn double_war() -> Result<(), Box<dyn std::error::Error>> {
+    let cards = |s: &str| parse_cards(s).expect("cards");
+    let deck = DealPattern::round_robin(2, 26).stack(Deck::default(), &[
+        (Destination::Seat(0), cards("KS 2S 3S 4S 9S 5S 6S 7S AS")),
+        (Destination::Seat(1), cards("KH 2H 3H 4H 9H 5H 6H 7H QH")),
+    ])?;
+    let mut log = EventLog::new();
+    let mut game = deal_deck(deck, &mut log)?;
+    let mut war_count = 0;
+
+    // Kings, then nines, then the ace beats the queen
+    round(&mut game, &mut war_count, &mut log)?;
+    assert_eq!(war_count, 1);
+    assert_eq!(log.events[3], Event::WarStarted { cards: cards("KS KH") });
+    assert_eq!(log.events[4], Event::WarStarted { cards: cards("9S 9H") });
+    assert!(matches!(&log.events[5], Event::RoundWon { player: 0, cards } if cards.len() == 18));
+    assert_eq!(game.hands[0].len(), 35);
+    assert_eq!(game.hands[1].len(), 17);
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+//! Multi-line ascii art for cards, hands and tables
+
+use super::*;
+use std::env;
+
+/// The width of a single card glyph in columns
+pub const CARD_WIDTH: usize = 7;
+
+/// The height of a single card glyph in lines
+pub const CARD_HEIGHT: usize = 5;
+
+/// How many columns of a card stay visible when it is overlapped in a fan
+pub const FAN_STEP: usize = 4;
+
+/// A card as it lies on the table: face up or face down
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Face {
+    Up(Card),
+    Down,
+}
+
+impl From<Card> for Face {
+    fn from(card: Card) -> Self {
+        Face::Up(card)
+    }
+}
+
+/// How a row of cards is laid out
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Spread {
+    /// Side by side with a space between each card
+    Row,
+    /// Overlapping so only the corner index of each card shows
+    Fan,
+    /// Squared up so only the left edge of each card shows
+    Stack,
+}
+
+impl Spread {
+    /// How many columns each card but the last takes up
+    pub fn step(self) -> usize {
+        match self {
+            Spread::Row => CARD_WIDTH + 1,
+            Spread::Fan => FAN_STEP,
+            Spread::Stack => 1,
+        }
+    }
+}
+
+/// Draws cards as boxed glyphs, wrapping rows to a terminal width
+#[derive(Debug, Copy, Clone)]
+pub struct Renderer {
+    pub width: usize,
+    pub spread: Spread,
+}
+
+impl Default for Renderer {
+    /// Uses `$COLUMNS` for the width if it is set, otherwise 80
+    fn default() -> Self {
+        let width = env::var("COLUMNS")
+            .ok()
+            .and_then(|c| c.parse().ok())
+            .unwrap_or(80);
+
+        Renderer::new(width, Spread::Row)
+    }
+}
+
+impl Renderer {
+    pub fn new(width: usize, spread: Spread) -> Self {
+        Renderer { width, spread }
+    }
+
+    /// The lines of a single card glyph
+    pub fn glyph(&self, face: &Face) -> Vec<String> {
+        let border = format!("+{}+", "-".repeat(CARD_WIDTH - 2));
+
+        match face {
+            Face::Up(card) => {
+                let index = format!("{}{}", card.value, card.suit);
+                vec![
+                    border.clone(),
+                    format!("|{:<1$}|", index, CARD_WIDTH - 2),
+                    format!("|{:^1$}|", card.suit.to_string(), CARD_WIDTH - 2),
+                    format!("|{:>1$}|", index, CARD_WIDTH - 2),
+                    border,
+                ]
+            }
+            Face::Down => {
+                let back = format!("|{}|", "#".repeat(CARD_WIDTH - 2));
+                vec![border.clone(), back.clone(), back.clone(), back, border]
+            }
+        }
+    }
+
+    /// Render a row of cards, wrapping to as many rows as the width needs
+    pub fn render(&self, faces: &[Face]) -> String {
+        let per_row = self.per_row();
+        let mut rows = Vec::new();
+
+        for chunk in faces.chunks(per_row) {
+            let glyphs: Vec<Vec<String>> = chunk.iter().map(|f| self.glyph(f)).collect();
+            rows.push(self.join(&glyphs));
+        }
+
+        rows.join("\n")
+    }
+
+    /// Render every card face up
+    pub fn cards<'a, C: Cards<'a>>(&self, cards: &'a C) -> String {
+        let faces: Vec<Face> = cards.cards().map(|c| Face::Up(*c)).collect();
+        self.render(&faces)
+    }
+
+    /// Render `count` cards face down
+    pub fn backs(&self, count: usize) -> String {
+        self.render(&vec![Face::Down; count])
+    }
+
+    /// How many cards fit across the width
+    pub fn per_row(&self) -> usize {
+        let step = self.spread.step();
+        if self.width < CARD_WIDTH {
+            1
+        } else {
+            ((self.width - CARD_WIDTH) / step + 1).max(1)
+        }
+    }
+
+    /// Lay the glyphs out left to right
+    ///
+    /// A card covered by the next one keeps its left `step` columns. Its
+    /// bottom index would be cut in half there, so the top index, which is
+    /// on the left, stands in for it.
+    fn join(&self, glyphs: &[Vec<String>]) -> String {
+        let step = self.spread.step();
+        let overlapped = step < CARD_WIDTH;
+        let mut lines = vec![String::new(); CARD_HEIGHT];
+
+        for (i, glyph) in glyphs.iter().enumerate() {
+            let last = i + 1 == glyphs.len();
+            for (row, line) in lines.iter_mut().enumerate() {
+                if last {
+                    line.push_str(&glyph[row]);
+                } else if overlapped && row == CARD_HEIGHT - 2 {
+                    line.push_str(&format!("{:<1$.1$}", glyph[1], step));
+                } else {
+                    line.push_str(&format!("{:<1$.1$}", glyph[row], step));
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+#[test]
+fn render_row_and_fan() {
+    let ten = Card::new(Value::Ten, Suit::Hearts);
+    let ace = Card::new(Value::Ace, Suit::Spades);
+
+    let row = Renderer::new(80, Spread::Row).render(&[ten.into(), Face::Down]);
+    assert_eq!(
+        row,
+        "+-----+ +-----+\n\
+         |10♥  | |#####|\n\
+         |  ♥  | |#####|\n\
+         |  10♥| |#####|\n\
+         +-----+ +-----+"
+    );
+
+    let fan = Renderer::new(80, Spread::Fan).render(&[ten.into(), ace.into()]);
+    assert_eq!(
+        fan,
+        "+---+-----+\n\
+         |10♥|A♠   |\n\
+         |  ♥|  ♠  |\n\
+         |10♥|   A♠|\n\
+         +---+-----+"
+    );
+
+    // Two cards won't fit side by side in 10 columns
+    let wrapped = Renderer::new(10, Spread::Row).backs(2);
+    assert_eq!(wrapped.lines().count(), CARD_HEIGHT * 2);
+}
@@ -0,0 +1,164 @@
+//! Checks that no card has gone missing or been duplicated during a game
+
+use super::*;
+use std::fmt;
+
+/// Every card has its own slot, so counting is just indexing
+const SLOTS: usize = 52;
+
+fn slot(card: &Card) -> usize {
+    card.value as usize * 4 + card.suit as usize
+}
+
+fn card(slot: usize) -> Card {
+    Card::new(VALUES[slot / 4], SUITS[slot % 4])
+}
+
+/// Where every card on the table is, zone by zone
+#[derive(Debug, Clone)]
+pub struct Census {
+    zones: Vec<String>,
+    /// The zones holding each card
+    seen: Vec<Vec<usize>>,
+}
+
+impl Default for Census {
+    fn default() -> Self {
+        Census {
+            zones: Vec::new(),
+            seen: vec![Vec::new(); SLOTS],
+        }
+    }
+}
+
+impl Census {
+    pub fn new() -> Self {
+        Census::default()
+    }
+
+    /// Count every card in a zone like "deck" or "Player 1 hand"
+    pub fn count<'a, C: Cards<'a>>(&mut self, zone: &str, cards: &'a C) -> &mut Self {
+        let index = self.zones.len();
+        self.zones.push(zone.into());
+        for card in cards.cards() {
+            self.seen[slot(card)].push(index);
+        }
+        self
+    }
+}
+
+/// The cards that a game should always hold, wherever they are
+#[derive(Debug, Clone)]
+pub struct Audit {
+    expected: Vec<usize>,
+}
+
+impl Default for Audit {
+    /// One standard 52 card deck
+    fn default() -> Self {
+        Audit::new(&Deck::default())
+    }
+}
+
+impl Audit {
+    pub fn new<'a, C: Cards<'a>>(cards: &'a C) -> Self {
+        let mut expected = vec![0; SLOTS];
+        for card in cards.cards() {
+            expected[slot(card)] += 1;
+        }
+        Audit { expected }
+    }
+
+    /// Check that the census found exactly the expected cards
+    pub fn check(&self, census: &Census) -> Result<(), AuditError> {
+        let mut violations = Vec::new();
+
+        for (slot, (expected, zones)) in self.expected.iter().zip(census.seen.iter()).enumerate() {
+            if zones.len() < *expected {
+                violations.push(Violation::Missing { card: card(slot), count: expected - zones.len() });
+            } else if zones.len() > *expected {
+                let zones = zones.iter().map(|z| census.zones[*z].clone()).collect();
+                violations.push(Violation::Extra { card: card(slot), zones });
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(AuditError { violations })
+        }
+    }
+}
+
+/// One card that is not where it should be
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Violation {
+    /// Fewer copies of the card than expected are anywhere on the table
+    Missing { card: Card, count: usize },
+    /// More copies of the card than expected, and every zone holding one
+    Extra { card: Card, zones: Vec<String> },
+}
+
+impl Violation {
+    pub fn card(&self) -> Card {
+        match self {
+            Violation::Missing { card, .. } | Violation::Extra { card, .. } => *card,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::Missing { card, count: 1 } => write!(f, "{} is missing", card),
+            Violation::Missing { card, count } => write!(f, "{} copies of {} are missing", count, card),
+            Violation::Extra { card, zones } => {
+                write!(f, "{} is in too many places: {}", card, zones.join(", "))
+            }
+        }
+    }
+}
+
+/// Every card that failed an audit
+#[derive(Debug, Clone)]
+pub struct AuditError {
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let violations: Vec<String> = self.violations.iter().map(|v| v.to_string()).collect();
+        write!(f, "Audit failed: {}", violations.join("; "))
+    }
+}
+
+impl std::error::Error for AuditError {}
+
+#[test]
+fn audit_zones() -> DealResult<()> {
+    let audit = Audit::default();
+    let mut game = Game::new(2, 5)?;
+
+    let census = |game: &Game| {
+        let mut census = Census::new();
+        census.count("deck", &game.deck)
+            .count("hand 0", &game.hands[0])
+            .count("hand 1", &game.hands[1]);
+        census
+    };
+    assert!(audit.check(&census(&game)).is_ok());
+
+    let lost = game.deck.give(0)?;
+    let copied = game.hands[0].cards().next().copied().expect("card");
+    game.hands[1].take(copied);
+
+    let err = audit.check(&census(&game)).expect_err("bad table");
+    assert_eq!(err.violations.len(), 2);
+    assert!(err.violations.contains(&Violation::Missing { card: lost, count: 1 }));
+    assert!(err.violations.contains(&Violation::Extra {
+        card: copied,
+        zones: vec!["hand 0".into(), "hand 1".into()],
+    }));
+
+    Ok(())
+}
@@ -0,0 +1,218 @@
+//! Deals that meet conditions, for teaching hands and testing games
+
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_pcg::Pcg64Mcg;
+
+use super::*;
+use std::collections::HashSet;
+use std::fmt;
+
+/// A condition on one seat's hand
+pub type HandPredicate = Box<dyn Fn(&Hand) -> bool>;
+
+/// Deals games until every seat's hand meets its conditions
+///
+/// Cards that must be in a hand are placed first, at random deal positions
+/// for that seat. The rest of the deck is shuffled around them and deals
+/// that fail a predicate are thrown away.
+pub struct ConstrainedDeal {
+    pub pattern: DealPattern,
+    /// How many deals to try before giving up
+    pub attempts: usize,
+    pub seed: u64,
+    placed: Vec<(usize, Card)>,
+    predicates: Vec<(usize, HandPredicate)>,
+}
+
+impl ConstrainedDeal {
+    pub fn new(pattern: DealPattern) -> Self {
+        ConstrainedDeal {
+            pattern,
+            attempts: 100_000,
+            seed: 0,
+            placed: Vec::new(),
+            predicates: Vec::new(),
+        }
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Deal these cards to the seat
+    pub fn place(mut self, seat: usize, cards: &[Card]) -> Self {
+        self.placed.extend(cards.iter().map(|card| (seat, *card)));
+        self
+    }
+
+    /// Only keep deals where the seat's hand passes the predicate
+    pub fn require<F: Fn(&Hand) -> bool + 'static>(mut self, seat: usize, predicate: F) -> Self {
+        self.predicates.push((seat, Box::new(predicate)));
+        self
+    }
+
+    /// Deal a game from a standard deck that meets every condition
+    pub fn deal(&self) -> Result<Game, ConstraintError> {
+        let destinations = self.pattern.destinations(52)?;
+        let seats = self.pattern.hand_sizes.len();
+
+        let mut positions = vec![Vec::new(); seats];
+        for (i, destination) in destinations.iter().enumerate() {
+            if let Destination::Seat(seat) = destination {
+                positions[*seat].push(i);
+            }
+        }
+        self.check_placed(&positions)?;
+
+        let placed: HashSet<Card> = self.placed.iter().map(|(_, card)| *card).collect();
+        let mut rest: Vec<Card> = Deck::default().cards.into_iter().filter(|c| !placed.contains(c)).collect();
+        let mut rng = Pcg64Mcg::seed_from_u64(self.seed);
+
+        for _ in 0..self.attempts {
+            let mut slots = vec![None; destinations.len()];
+            for (seat, positions) in positions.iter_mut().enumerate() {
+                positions.shuffle(&mut rng);
+                let cards = self.placed.iter().filter(|(s, _)| *s == seat);
+                for (position, (_, card)) in positions.iter().zip(cards) {
+                    slots[*position] = Some(*card);
+                }
+            }
+
+            rest.shuffle(&mut rng);
+            let mut rest = rest.iter();
+            let cards = slots
+                .into_iter()
+                .map(|slot| slot.or_else(|| rest.next().copied()))
+                .collect::<Option<Vec<Card>>>()
+                .ok_or(DealError::NothingToGive)?;
+
+            let game = Game::with_pattern(Deck { cards }, &self.pattern)?;
+            if self.predicates.iter().all(|(seat, p)| p(&game.hands[*seat])) {
+                return Ok(game);
+            }
+        }
+
+        Err(ConstraintError::GaveUp { attempts: self.attempts })
+    }
+
+    /// Make sure every placed card can go where it is asked to
+    fn check_placed(&self, positions: &[Vec<usize>]) -> Result<(), ConstraintError> {
+        let mut seen = HashSet::new();
+        let mut per_seat = vec![0; positions.len()];
+
+        for (seat, card) in self.placed.iter() {
+            if !seen.insert(*card) {
+                return Err(ConstraintError::Unsatisfiable(format!("{} is placed twice", card)));
+            }
+            match per_seat.get_mut(*seat) {
+                Some(count) => *count += 1,
+                None => return Err(ConstraintError::Unsatisfiable(format!("there is no seat {}", seat))),
+            }
+        }
+
+        for (seat, (count, positions)) in per_seat.iter().zip(positions.iter()).enumerate() {
+            if *count > positions.len() {
+                return Err(ConstraintError::Unsatisfiable(format!(
+                    "seat {} is dealt {} cards but has {} placed",
+                    seat,
+                    positions.len(),
+                    count
+                )));
+            }
+        }
+
+        for (seat, _) in self.predicates.iter() {
+            if *seat >= positions.len() {
+                return Err(ConstraintError::Unsatisfiable(format!("there is no seat {}", seat)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Why no deal was found
+#[derive(Debug)]
+pub enum ConstraintError {
+    /// The conditions contradict each other, so no deal could meet them
+    Unsatisfiable(String),
+    /// No deal met the conditions within the attempt limit
+    GaveUp { attempts: usize },
+    Deal(DealError),
+}
+
+impl From<DealError> for ConstraintError {
+    fn from(e: DealError) -> Self {
+        ConstraintError::Deal(e)
+    }
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConstraintError::Unsatisfiable(why) => write!(f, "Unsatisfiable: {}", why),
+            ConstraintError::GaveUp { attempts } => write!(f, "No deal found in {} attempts", attempts),
+            ConstraintError::Deal(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConstraintError {}
+
+/// Bridge high card points: 4 for an ace, 3 for a king, 2 for a queen and
+/// 1 for a jack
+pub fn high_card_points(hand: &Hand) -> u32 {
+    hand.cards()
+        .map(|card| match card.value {
+            Value::Ace => 4,
+            Value::King => 3,
+            Value::Queen => 2,
+            Value::Jack => 1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// No singletons or voids, and at most one doubleton
+pub fn is_balanced(hand: &Hand) -> bool {
+    let lengths: Vec<usize> = SUITS.iter().map(|suit| hand.cards().filter(|c| c.suit == *suit).count()).collect();
+    lengths.iter().all(|n| *n >= 2) && lengths.iter().filter(|n| **n == 2).count() <= 1
+}
+
+/// Whether two cards in the hand share a value
+pub fn has_pair(hand: &Hand) -> bool {
+    VALUES.iter().any(|value| hand.cards().filter(|c| c.value == *value).count() >= 2)
+}
+
+#[test]
+fn constrained_deals() -> Result<(), ConstraintError> {
+    let aces = [Card::new(Value::Ace, Suit::Spades), Card::new(Value::Ace, Suit::Hearts)];
+
+    let pattern = DealPattern::round_robin(4, 13);
+    let dealer = pattern.dealer;
+    let game = ConstrainedDeal::new(pattern.clone())
+        .seed(7)
+        .place(0, &aces)
+        .require(1, |hand| (15..=17).contains(&high_card_points(hand)) && is_balanced(hand))
+        .require(dealer, has_pair)
+        .deal()?;
+    assert!(aces.iter().all(|ace| game.hands[0].cards.contains(ace)));
+    assert!((15..=17).contains(&high_card_points(&game.hands[1])));
+    assert!(is_balanced(&game.hands[1]));
+    assert!(has_pair(&game.hands[dealer]));
+
+    let twice = ConstrainedDeal::new(pattern.clone()).place(0, &aces).place(1, &aces[..1]);
+    assert!(matches!(twice.deal(), Err(ConstraintError::Unsatisfiable(_))));
+
+    let impossible = ConstrainedDeal::new(pattern).attempts(10).require(2, |hand| high_card_points(hand) > 37);
+    assert!(matches!(impossible.deal(), Err(ConstraintError::GaveUp { attempts: 10 })));
+
+    Ok(())
+}
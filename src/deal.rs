@@ -77,7 +77,8 @@ impl Give for Hand {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DealError {
     NothingToGive,
     CannotTake,
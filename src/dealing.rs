@@ -0,0 +1,227 @@
+//! The ways cards go around the table when a game is dealt
+
+use super::*;
+
+/// Cards dealt in one go, to each seat or to the kitty
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Packet {
+    /// Every seat in turn gets this many cards
+    Each(usize),
+    /// The kitty, skat or widow gets this many cards
+    Kitty(usize),
+}
+
+/// Where the cards left over after the hands are full go
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Leftovers {
+    /// They stay in the deck, to draw from later
+    Stock,
+    /// They all go to the kitty
+    Kitty,
+}
+
+/// How a deal goes around the table
+///
+/// The packets are one time around the table, repeated until every hand is
+/// full. Seats that are full are passed over, and kitty packets are only
+/// dealt the first time around.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DealPattern {
+    /// How many cards each seat ends up with
+    pub hand_sizes: Vec<usize>,
+    pub packets: Vec<Packet>,
+    /// The seat that deals; the seat on its left gets the first cards
+    pub dealer: usize,
+    pub leftovers: Leftovers,
+}
+
+impl DealPattern {
+    /// One card at a time to each seat, with the last seat dealing
+    pub fn round_robin(seats: usize, hand_size: usize) -> Self {
+        DealPattern::uneven(&vec![hand_size; seats])
+    }
+
+    /// One card at a time, until each seat has its own number of cards
+    pub fn uneven(hand_sizes: &[usize]) -> Self {
+        DealPattern {
+            hand_sizes: hand_sizes.to_vec(),
+            packets: vec![Packet::Each(1)],
+            dealer: hand_sizes.len().saturating_sub(1),
+            leftovers: Leftovers::Stock,
+        }
+    }
+
+    /// Packets of these sizes to each seat, like 3 then 2 in Euchre
+    pub fn packets(seats: usize, sizes: &[usize]) -> Self {
+        DealPattern {
+            packets: sizes.iter().map(|n| Packet::Each(*n)).collect(),
+            ..DealPattern::round_robin(seats, sizes.iter().sum())
+        }
+    }
+
+    /// Where each card from the top of a deck of `len` cards ends up
+    pub fn destinations(&self, len: usize) -> DealResult<Vec<Destination>> {
+        let seats = self.hand_sizes.len();
+        let mut counts = vec![0; seats];
+        let mut destinations = Vec::with_capacity(len);
+        let mut first = true;
+
+        while seats > 0 && counts.iter().zip(self.hand_sizes.iter()).any(|(c, size)| c < size) {
+            let before = destinations.len();
+
+            for packet in self.packets.iter() {
+                match *packet {
+                    Packet::Each(n) => {
+                        for i in 1..=seats {
+                            let seat = (self.dealer + i) % seats;
+                            let wanted = self.hand_sizes[seat].saturating_sub(counts[seat]);
+                            counts[seat] += n.min(wanted);
+                            destinations.extend((0..n.min(wanted)).map(|_| Destination::Seat(seat)));
+                        }
+                    }
+                    Packet::Kitty(n) if first => {
+                        destinations.extend((0..n).map(|_| Destination::Kitty));
+                    }
+                    Packet::Kitty(_) => (),
+                }
+            }
+
+            first = false;
+            if destinations.iter().skip(before).all(|d| *d == Destination::Kitty) {
+                break;
+            }
+        }
+
+        if destinations.len() > len {
+            return Err(DealError::NothingToGive);
+        }
+
+        let rest = match self.leftovers {
+            Leftovers::Stock => Destination::Stock,
+            Leftovers::Kitty => Destination::Kitty,
+        };
+        destinations.resize(len, rest);
+        Ok(destinations)
+    }
+
+    /// Order a deck so that dealing it puts these cards where they're wanted
+    ///
+    /// Each destination is dealt its cards in the order given, so
+    /// `(Destination::Seat(0), cards)` makes `cards` the first cards seat 0
+    /// gets. Cards for the stock end up on top of it. Every other card fills
+    /// the places left over, in the order it had in `deck`.
+    pub fn stack(&self, deck: Deck, wanted: &[(Destination, Vec<Card>)]) -> DealResult<Deck> {
+        let destinations = self.destinations(deck.len())?;
+        let mut slots = vec![None; destinations.len()];
+        let mut rest = deck.cards;
+
+        for (destination, cards) in wanted.iter() {
+            let places: Vec<usize> = (0..slots.len())
+                .filter(|i| destinations[*i] == *destination && slots[*i].is_none())
+                .collect();
+            if places.len() < cards.len() {
+                return Err(DealError::CannotTake);
+            }
+
+            for (place, card) in places.into_iter().zip(cards.iter()) {
+                let index = rest.iter().position(|c| c == card).ok_or(DealError::NothingToGive)?;
+                slots[place] = Some(rest.remove(index));
+            }
+        }
+
+        let mut rest = rest.into_iter();
+        slots
+            .into_iter()
+            .map(|slot| slot.or_else(|| rest.next()))
+            .collect::<Option<Vec<Card>>>()
+            .map(Deck::from)
+            .ok_or(DealError::NothingToGive)
+    }
+
+    /// Deal from the top of the deck into the hands and the kitty
+    pub fn deal(&self, deck: &mut Deck, hands: &mut [Hand], kitty: &mut Hand) -> DealResult<()> {
+        let destinations = self.destinations(deck.len())?;
+        if destinations.iter().any(|d| matches!(d, Destination::Seat(seat) if *seat >= hands.len())) {
+            return Err(DealError::OutOfBounds);
+        }
+        let mut stock = Vec::new();
+
+        for (card, destination) in deck.cards.drain(..).zip(destinations) {
+            match destination {
+                Destination::Seat(seat) => hands[seat].take(card),
+                Destination::Kitty => kitty.take(card),
+                Destination::Stock => stock.push(card),
+            }
+        }
+
+        deck.cards = stock;
+        Ok(())
+    }
+}
+
+/// Where one card goes in a deal
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Destination {
+    Seat(usize),
+    Kitty,
+    /// Left in the deck
+    Stock,
+}
+
+#[test]
+fn skat_and_uneven_deals() -> DealResult<()> {
+    let order = Deck::default().cards;
+
+    let skat = DealPattern {
+        packets: vec![Packet::Each(3), Packet::Kitty(2), Packet::Each(4), Packet::Each(3)],
+        leftovers: Leftovers::Kitty,
+        ..DealPattern::packets(3, &[3, 4, 3])
+    };
+    let game = Game::with_pattern(Deck::default(), &skat)?;
+    assert_eq!(game.hands[0].cards[..3], order[..3]);
+    assert_eq!(game.hands[1].cards[3..7], order[15..19]);
+    assert_eq!(game.kitty.cards[..2], order[9..11]);
+    assert_eq!(game.kitty.len(), 22);
+    assert!(game.deck.is_empty());
+
+    let uneven = DealPattern { dealer: 0, ..DealPattern::uneven(&[3, 2]) };
+    let game = Game::with_pattern(Deck::default(), &uneven)?;
+    assert_eq!(game.hands[0].cards, vec![order[1], order[3], order[4]]);
+    assert_eq!(game.hands[1].cards, vec![order[0], order[2]]);
+    assert_eq!(game.deck.len(), 47);
+
+    Ok(())
+}
+
+#[test]
+fn deal_destinations() {
+    assert!(DealPattern::round_robin(4, 14).destinations(52).is_err());
+
+    // Too few hands for the pattern leaves the deck as it was
+    let euchre = DealPattern::packets(4, &[3, 2]);
+    let mut deck = Deck::default();
+    let mut hands = vec![Hand::new(); 3];
+    assert_eq!(euchre.deal(&mut deck, &mut hands, &mut Hand::new()), Err(DealError::OutOfBounds));
+    assert_eq!(deck, Deck::default());
+    assert!(hands.iter().all(Hand::is_empty));
+}
+
+#[test]
+fn stacked_deals() -> DealResult<()> {
+    let euchre = DealPattern::packets(4, &[3, 2]);
+    let wanted = [
+        (Destination::Seat(2), parse_cards("JH JD AH KH QH").expect("cards")),
+        (Destination::Stock, parse_cards("9S").expect("cards")),
+    ];
+    let deck = euchre.stack(Deck::default(), &wanted)?;
+    let game = Game::with_pattern(deck, &euchre)?;
+    assert_eq!(game.hands[2], "JH JD AH KH QH".parse().expect("hand"));
+    assert_eq!(game.deck.cards().next(), Some(&Card::new(Value::Nine, Suit::Spades)));
+    assert_eq!(game.deck.len() + game.hands.iter().map(Hand::len).sum::<usize>(), 52);
+    assert!(euchre.stack(Deck::default(), &[(Destination::Kitty, wanted[1].1.clone())]).is_err());
+
+    Ok(())
+}
@@ -76,7 +76,7 @@ impl fmt::Debug for Deck {
 
         while let Some(card) = cards_iter.next() {
             cards.push_str(&card.to_string());
-            if let Some(_) = cards_iter.peek() {
+            if cards_iter.peek().is_some() {
                 cards.push_str(", ");
             }
         }
@@ -92,7 +92,7 @@ impl fmt::Debug for Hand {
 
         while let Some(card) = cards_iter.next() {
             cards.push_str(&card.to_string());
-            if let Some(_) = cards_iter.peek() {
+            if cards_iter.peek().is_some() {
                 cards.push_str(", ");
             }
         }
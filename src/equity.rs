@@ -0,0 +1,451 @@
+//! How often each poker hand wins, by dealing out the rest of the board
+
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+
+use super::*;
+use std::collections::HashSet;
+use std::fmt;
+
+/// The boards are split into this many chunks, each with its own random
+/// stream, so a seed gives the same report on any number of threads
+const CHUNKS: usize = 64;
+
+/// Which showdown decides the pot
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Showdown {
+    /// The best five of two hole cards and the board
+    Holdem,
+    /// Exactly two hole cards and three from the board
+    Omaha,
+    /// Omaha split between the best high and the best eight-or-better low
+    OmahaHiLo,
+}
+
+/// Equity for hands with part of the board, and any dead cards, known
+///
+/// When there are at most `exact_limit` ways to finish the board, every
+/// one is dealt; otherwise `trials` random boards are dealt from the stub,
+/// which is the deck without the known cards.
+#[derive(Debug, Clone)]
+pub struct EquityCalc {
+    pub showdown: Showdown,
+    pub hands: Vec<Vec<Card>>,
+    pub board: Vec<Card>,
+    pub dead: Vec<Card>,
+    pub exact_limit: u64,
+    pub trials: u64,
+    pub seed: u64,
+    pub threads: usize,
+}
+
+impl EquityCalc {
+    pub fn new(showdown: Showdown, hands: &[Hand]) -> Self {
+        EquityCalc {
+            showdown,
+            hands: hands.iter().map(|h| h.cards.clone()).collect(),
+            board: Vec::new(),
+            dead: Vec::new(),
+            exact_limit: 100_000,
+            trials: 100_000,
+            seed: 0,
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    pub fn board(mut self, board: &[Card]) -> Self {
+        self.board = board.to_vec();
+        self
+    }
+
+    /// Cards known to be out of play, like folded hands or burns
+    pub fn dead(mut self, dead: &[Card]) -> Self {
+        self.dead = dead.to_vec();
+        self
+    }
+
+    /// Deal every board when there are at most this many
+    pub fn exact_limit(mut self, limit: u64) -> Self {
+        self.exact_limit = limit;
+        self
+    }
+
+    /// How many random boards to deal when there are too many to list
+    pub fn trials(mut self, trials: u64) -> Self {
+        self.trials = trials;
+        self
+    }
+
+    /// Random boards dealt with the same seed give the same report, however
+    /// many threads deal them
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// The cards left to finish the board from
+    pub fn stub(&self) -> Vec<Card> {
+        let known: HashSet<&Card> = self.hands.iter().flatten().chain(&self.board).chain(&self.dead).collect();
+        Deck::default().cards.into_iter().filter(|c| !known.contains(c)).collect()
+    }
+
+    /// Work out each hand's equity
+    pub fn run(&self) -> Result<EquityReport, EquityError> {
+        self.check()?;
+        let stub = self.stub();
+        let needed = 5 - self.board.len();
+        if stub.len() < needed {
+            return Err(EquityError::StubTooSmall);
+        }
+
+        let runouts = combinations(stub.len() as u64, needed as u64);
+        let exact = runouts <= self.exact_limit;
+        // The fields are public, so the builder's bounds may have been skipped
+        let threads = self.threads.clamp(1, CHUNKS);
+        let mut chunks: Vec<(usize, Tally)> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|t| {
+                    let stub = &stub;
+                    scope.spawn(move || {
+                        (t..CHUNKS)
+                            .step_by(threads)
+                            .map(|c| {
+                                let tally = if exact {
+                                    self.enumerate(stub, needed, c, runouts)
+                                } else {
+                                    self.sample(stub, needed, c)
+                                };
+                                (c, tally)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|w| w.join().expect("equity thread")).collect()
+        });
+
+        // Add the chunks up in the same order whatever the thread count
+        chunks.sort_by_key(|(c, _)| *c);
+        let tally = chunks
+            .into_iter()
+            .fold(Tally::new(self.hands.len()), |total, (_, t)| total.merge(t));
+        Ok(tally.report(exact))
+    }
+
+    fn check(&self) -> Result<(), EquityError> {
+        let holes = match self.showdown {
+            Showdown::Holdem => 2..=2,
+            Showdown::Omaha | Showdown::OmahaHiLo => 4..=6,
+        };
+        if self.hands.len() < 2 {
+            return Err(EquityError::TooFewHands);
+        }
+        if let Some(seat) = self.hands.iter().position(|h| !holes.contains(&h.len())) {
+            return Err(EquityError::HoleCards(seat));
+        }
+        if self.board.len() > 5 || self.board.len() == 1 || self.board.len() == 2 {
+            return Err(EquityError::Board);
+        }
+
+        let mut seen = HashSet::new();
+        for card in self.hands.iter().flatten().chain(&self.board).chain(&self.dead) {
+            if !seen.insert(card) {
+                return Err(EquityError::Duplicate(*card));
+            }
+        }
+        Ok(())
+    }
+
+    /// Deal one chunk's share of every board: the chunks split the boards,
+    /// in order, into runs of nearly equal length
+    fn enumerate(&self, stub: &[Card], needed: usize, chunk: usize, runouts: u64) -> Tally {
+        let chunks = CHUNKS as u64;
+        let start = runouts * chunk as u64 / chunks;
+        let end = runouts * (chunk as u64 + 1) / chunks;
+        let mut tally = Tally::new(self.hands.len());
+        let mut board = self.board.clone();
+        let mut picks = nth_combination(stub.len(), needed, start);
+
+        for _ in start..end {
+            board.truncate(self.board.len());
+            board.extend(picks.iter().map(|i| stub[*i]));
+            tally.add(&self.pots(&board));
+            next_combination(&mut picks, stub.len());
+        }
+        tally
+    }
+
+    /// Deal one chunk's share of the random boards
+    fn sample(&self, stub: &[Card], needed: usize, chunk: usize) -> Tally {
+        let mut rng = Pcg64Mcg::seed_from_u64(self.seed.wrapping_add(chunk as u64));
+        let mut tally = Tally::new(self.hands.len());
+        let mut stub = stub.to_vec();
+        let mut board = self.board.clone();
+        let chunks = CHUNKS as u64;
+        let trials = self.trials / chunks + u64::from((chunk as u64) < self.trials % chunks);
+
+        for _ in 0..trials {
+            board.truncate(self.board.len());
+            for i in 0..needed {
+                let j = rng.gen_range(i, stub.len());
+                stub.swap(i, j);
+                board.push(stub[i]);
+            }
+            tally.add(&self.pots(&board));
+        }
+        tally
+    }
+
+    /// Each hand's share of each equal part of the pot on a full board
+    fn pots(&self, board: &[Card]) -> Vec<Vec<f64>> {
+        match self.showdown {
+            Showdown::Holdem => {
+                let mut seven = [Card::default(); 7];
+                seven[2..].copy_from_slice(board);
+                let strengths: Vec<Option<Strength>> = self.hands
+                    .iter()
+                    .map(|hole| {
+                        seven[..2].copy_from_slice(hole);
+                        Some(evaluate_unchecked(&seven))
+                    })
+                    .collect();
+                vec![pot_shares(&strengths)]
+            }
+            Showdown::Omaha => {
+                let strengths: Vec<Option<Strength>> = self.hands
+                    .iter()
+                    .map(|hole| omaha_high(hole, board).map(|best| best.strength))
+                    .collect();
+                vec![pot_shares(&strengths)]
+            }
+            Showdown::OmahaHiLo => {
+                let hands: Vec<HiLo> = self.hands
+                    .iter()
+                    .map(|hole| HiLo::omaha(hole, board).expect("checked hand sizes"))
+                    .collect();
+                hi_lo_halves(&hands)
+            }
+        }
+    }
+}
+
+/// How many ways to choose k of n
+fn combinations(n: u64, k: u64) -> u64 {
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+/// The `rank`th way, in lexicographic order, to choose k of n
+fn nth_combination(n: usize, k: usize, mut rank: u64) -> Vec<usize> {
+    let mut picks = Vec::with_capacity(k);
+    let mut next = 0;
+    for i in 0..k {
+        // Skip every choice that starts with a smaller card here
+        loop {
+            let after = combinations((n - next - 1) as u64, (k - i - 1) as u64);
+            if rank < after {
+                break;
+            }
+            rank -= after;
+            next += 1;
+        }
+        picks.push(next);
+        next += 1;
+    }
+    picks
+}
+
+/// Step to the next way to choose from n, staying put after the last
+fn next_combination(picks: &mut [usize], n: usize) {
+    let k = picks.len();
+    if let Some(i) = (0..k).rev().find(|i| picks[*i] < n - k + i) {
+        picks[i] += 1;
+        for j in i + 1..k {
+            picks[j] = picks[j - 1] + 1;
+        }
+    }
+}
+
+/// Running totals for each hand over the boards dealt
+struct Tally {
+    boards: u64,
+    wins: Vec<u64>,
+    ties: Vec<u64>,
+    shares: Vec<f64>,
+    squares: Vec<f64>,
+}
+
+impl Tally {
+    fn new(hands: usize) -> Self {
+        Tally { boards: 0, wins: vec![0; hands], ties: vec![0; hands], shares: vec![0.0; hands], squares: vec![0.0; hands] }
+    }
+
+    /// Count one board, split into pots of equal size like the high and
+    /// low halves
+    fn add(&mut self, pots: &[Vec<f64>]) {
+        self.boards += 1;
+        for i in 0..self.wins.len() {
+            let parts: Vec<f64> = pots.iter().map(|pot| pot[i]).collect();
+            if parts.iter().any(|p| *p > 0.0 && *p < 1.0) {
+                self.ties[i] += 1;
+            } else if parts.contains(&1.0) {
+                self.wins[i] += 1;
+            }
+            let share = parts.iter().sum::<f64>() / pots.len() as f64;
+            self.shares[i] += share;
+            self.squares[i] += share * share;
+        }
+    }
+
+    fn merge(mut self, other: Tally) -> Self {
+        self.boards += other.boards;
+        for i in 0..self.wins.len() {
+            self.wins[i] += other.wins[i];
+            self.ties[i] += other.ties[i];
+            self.shares[i] += other.shares[i];
+            self.squares[i] += other.squares[i];
+        }
+        self
+    }
+
+    fn report(&self, exact: bool) -> EquityReport {
+        let n = self.boards.max(1) as f64;
+        let hands = (0..self.wins.len())
+            .map(|i| {
+                let equity = self.shares[i] / n;
+                let variance = (self.squares[i] / n - equity * equity).max(0.0);
+                let (win, tie) = (self.wins[i] as f64 / n, self.ties[i] as f64 / n);
+                HandEquity {
+                    win,
+                    tie,
+                    loss: 1.0 - win - tie,
+                    equity,
+                    margin: if exact { 0.0 } else { 1.96 * (variance / n).sqrt() },
+                }
+            })
+            .collect();
+        EquityReport { hands, boards: self.boards, exact }
+    }
+}
+
+/// How one hand fares, as fractions of the boards dealt
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HandEquity {
+    /// Won the pot, or in high-low won a half, without sharing it
+    pub win: f64,
+    /// Shared the pot, or one of its halves
+    pub tie: f64,
+    pub loss: f64,
+    /// The average share of the pot
+    pub equity: f64,
+    /// Half the width of the 95% confidence interval for the equity, or
+    /// zero when every board was dealt
+    pub margin: f64,
+}
+
+impl HandEquity {
+    /// The 95% confidence interval for the equity
+    pub fn interval(&self) -> (f64, f64) {
+        (self.equity - self.margin, self.equity + self.margin)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquityReport {
+    pub hands: Vec<HandEquity>,
+    /// How many boards were dealt
+    pub boards: u64,
+    /// Whether every possible board was dealt
+    pub exact: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EquityError {
+    TooFewHands,
+    /// This seat has the wrong number of hole cards
+    HoleCards(usize),
+    /// A board must have no cards, or three to five
+    Board,
+    Duplicate(Card),
+    StubTooSmall,
+}
+
+impl fmt::Display for EquityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EquityError::TooFewHands => write!(f, "Equity needs at least two hands"),
+            EquityError::HoleCards(seat) => write!(f, "Hand {} has the wrong number of hole cards", seat),
+            EquityError::Board => write!(f, "The board must have 0, 3, 4 or 5 cards"),
+            EquityError::Duplicate(card) => write!(f, "{} is known more than once", card),
+            EquityError::StubTooSmall => write!(f, "Not enough cards left to finish the board"),
+        }
+    }
+}
+
+impl std::error::Error for EquityError {}
+
+#[test]
+fn equity() -> Result<(), EquityError> {
+    let hand = |s: &str| s.parse::<Hand>().expect("hand");
+    let cards = |s: &str| parse_cards(s).expect("cards");
+    let (aces, kings) = (hand("AH AS"), hand("KD KC"));
+
+    // Only the two other aces save the aces on the river
+    let turn = EquityCalc::new(Showdown::Holdem, &[aces.clone(), kings.clone()])
+        .board(&cards("KS 7D 2C 3H"))
+        .run()?;
+    assert!(turn.exact);
+    assert_eq!(turn.boards, 44);
+    assert!((turn.hands[0].equity - 2.0 / 44.0).abs() < 1e-9);
+    assert!((turn.hands[1].win - 42.0 / 44.0).abs() < 1e-9);
+
+    // Every thread count, even one set straight on the field, deals the same boards
+    let mut unthreaded = EquityCalc::new(Showdown::Holdem, &[aces.clone(), kings.clone()]).board(&cards("KS 7D 2C"));
+    unthreaded.threads = 0;
+    let flop = unthreaded.run()?;
+    assert_eq!(flop.boards, 990);
+    assert_eq!(unthreaded.clone().threads(5).run()?, flop);
+    assert_eq!(nth_combination(5, 3, 9), vec![2, 3, 4]);
+
+    // Aces are about 82% against kings before the flop
+    let preflop = EquityCalc::new(Showdown::Holdem, &[aces.clone(), kings.clone()])
+        .trials(20_000)
+        .threads(4)
+        .seed(7)
+        .run()?;
+    assert!(!preflop.exact);
+    assert_eq!(preflop.boards, 20_000);
+    let (low, high) = preflop.hands[0].interval();
+    assert!(low < 0.82 && 0.82 < high, "{:?}", preflop.hands[0]);
+    let total: f64 = preflop.hands.iter().map(|h| h.equity).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+
+    // The same ace-king splits every pot on this board
+    let chop = EquityCalc::new(Showdown::Holdem, &[hand("AH KH"), hand("AD KD")])
+        .board(&cards("AC AS KC KS QC"))
+        .run()?;
+    assert_eq!(chop.hands[0].tie, 1.0);
+    assert_eq!(chop.hands[0].equity, 0.5);
+
+    let omaha = EquityCalc::new(Showdown::OmahaHiLo, &[hand("AS 2S QC QH"), hand("KS 9C AH JD")])
+        .board(&cards("3C 4D 8S KH KD"))
+        .run()?;
+    assert_eq!(omaha.hands[0].equity, 0.5);
+    assert_eq!((omaha.hands[0].win, omaha.hands[1].win), (1.0, 1.0));
+
+    // The seed alone decides the random boards, not the thread count
+    let seeded = |threads| {
+        EquityCalc::new(Showdown::Holdem, &[hand("AH KH"), hand("7C 7D")]).trials(5_000).seed(3).threads(threads).run()
+    };
+    assert_eq!(seeded(1)?, seeded(3)?);
+
+    let duplicate = EquityCalc::new(Showdown::Holdem, &[aces.clone(), kings]).dead(&cards("AH")).run();
+    assert_eq!(duplicate, Err(EquityError::Duplicate(Card::new(Value::Ace, Suit::Hearts))));
+    assert_eq!(EquityCalc::new(Showdown::Holdem, &[aces]).run(), Err(EquityError::TooFewHands));
+
+    Ok(())
+}
@@ -0,0 +1,308 @@
+//! A typed record of everything that happens in a game, and a replay of it
+
+use super::*;
+use std::fmt;
+
+/// Something that happened in a game
+///
+/// Players are referred to by their seat number.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "event"))]
+pub enum Event {
+    /// A new game with these players and this many cards left to deal
+    Started { players: Vec<String>, stock: usize },
+    /// Cards dealt from the stock to a player
+    Dealt { player: usize, cards: Vec<Card> },
+    /// A player asked another for cards of a value
+    Asked { player: usize, target: usize, value: Value },
+    /// Cards handed from one player to another
+    Gave { from: usize, to: usize, cards: Vec<Card> },
+    /// A player drew from the stock
+    WentFishing { player: usize, card: Card },
+    /// Pairs moved from a player's hand to their pile
+    PairsDiscarded { player: usize, cards: Vec<Card> },
+    /// A player won these cards from the table
+    RoundWon { player: usize, cards: Vec<Card> },
+    /// Equal cards started a war
+    WarStarted { cards: Vec<Card> },
+    GameOver { winner: Option<usize> },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Started { players, stock } => {
+                write!(f, "Started with {} players and {} cards", players.len(), stock)
+            }
+            Event::Dealt { player, cards } => {
+                write!(f, "Player {} was dealt {:?}", player, Hand::from(cards.clone()))
+            }
+            Event::Asked { player, target, value } => write!(
+                f,
+                "Player {} asked Player {} for {}",
+                player,
+                target,
+                Language::English.value_plural(*value),
+            ),
+            Event::Gave { from, to, cards } => {
+                write!(f, "Player {} gave Player {} {:?}", from, to, Hand::from(cards.clone()))
+            }
+            Event::WentFishing { player, card } => {
+                write!(f, "Player {} went fishing and caught {}", player, card)
+            }
+            Event::PairsDiscarded { player, cards } => {
+                write!(f, "Player {} discarded {:?}", player, Hand::from(cards.clone()))
+            }
+            Event::RoundWon { player, cards } => {
+                write!(f, "Player {} won {:?}", player, Hand::from(cards.clone()))
+            }
+            Event::WarStarted { cards } => write!(f, "War! {:?}", Hand::from(cards.clone())),
+            Event::GameOver { winner: Some(player) } => write!(f, "Game over: Player {} wins", player),
+            Event::GameOver { winner: None } => write!(f, "Game over: nobody wins"),
+        }
+    }
+}
+
+/// Every event of a game in order
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventLog {
+    pub events: Vec<Event>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        EventLog::default()
+    }
+
+    pub fn push(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Write one JSON object per line
+    #[cfg(feature = "serde")]
+    pub fn write_jsonl<W: std::io::Write>(&self, mut w: W) -> io::Result<()> {
+        for event in self.events.iter() {
+            serde_json::to_writer(&mut w, event)?;
+            writeln!(w)?;
+        }
+        w.flush()
+    }
+
+    /// Read a log written by `write_jsonl`, skipping blank lines
+    #[cfg(feature = "serde")]
+    pub fn read_jsonl<R: std::io::BufRead>(r: R) -> io::Result<Self> {
+        let mut events = Vec::new();
+        for line in r.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                events.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(EventLog { events })
+    }
+}
+
+/// The table as it stands after some number of events
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub players: Vec<String>,
+    pub hands: Vec<Hand>,
+    /// Cards each player has set aside, like Go Fish pairs
+    pub piles: Vec<Hand>,
+    /// How many cards are left to deal or fish from
+    pub stock: usize,
+    pub winner: Option<usize>,
+}
+
+impl Snapshot {
+    /// Move the cards the way the event says they moved
+    pub fn apply(&mut self, event: &Event) {
+        match event {
+            Event::Started { players, stock } => {
+                self.players = players.clone();
+                self.seat(players.len().saturating_sub(1));
+                self.stock = *stock;
+            }
+            Event::Dealt { player, cards } => {
+                self.seat(*player);
+                self.stock = self.stock.saturating_sub(cards.len());
+                self.hands[*player].cards.extend(cards.iter().copied());
+            }
+            Event::Gave { from, to, cards } => {
+                self.seat(*from.max(to));
+                for card in cards.iter() {
+                    remove(&mut self.hands[*from], card);
+                    self.hands[*to].take(*card);
+                }
+            }
+            Event::WentFishing { player, card } => {
+                self.seat(*player);
+                self.stock = self.stock.saturating_sub(1);
+                self.hands[*player].take(*card);
+            }
+            Event::PairsDiscarded { player, cards } => {
+                self.seat(*player);
+                for card in cards.iter() {
+                    remove(&mut self.hands[*player], card);
+                    self.piles[*player].take(*card);
+                }
+            }
+            Event::RoundWon { player, cards } => {
+                self.seat(*player);
+                for card in cards.iter() {
+                    for hand in self.hands.iter_mut() {
+                        if remove(hand, card) {
+                            break;
+                        }
+                    }
+                    self.hands[*player].take(*card);
+                }
+            }
+            Event::GameOver { winner } => self.winner = *winner,
+            Event::Asked { .. } | Event::WarStarted { .. } => (),
+        }
+    }
+
+    /// Make sure there is a hand and a pile for this seat
+    fn seat(&mut self, player: usize) {
+        while self.hands.len() <= player {
+            self.hands.push(Hand::new());
+            self.piles.push(Hand::new());
+        }
+        while self.players.len() < self.hands.len() {
+            self.players.push(format!("Player {}", self.players.len()));
+        }
+    }
+}
+
+fn remove(hand: &mut Hand, card: &Card) -> bool {
+    match hand.cards.iter().position(|c| c == card) {
+        Some(index) => {
+            hand.cards.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Steps forward and backward through a recorded game
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub log: EventLog,
+    snapshots: Vec<Snapshot>,
+    position: usize,
+}
+
+impl Replay {
+    pub fn new(log: EventLog) -> Self {
+        let mut snapshots = vec![Snapshot::default()];
+        for event in log.events.iter() {
+            let mut next = snapshots[snapshots.len() - 1].clone();
+            next.apply(event);
+            snapshots.push(next);
+        }
+
+        Replay {
+            log,
+            snapshots,
+            position: 0,
+        }
+    }
+
+    /// How many events have been played
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The table after the events played so far
+    pub fn snapshot(&self) -> &Snapshot {
+        &self.snapshots[self.position]
+    }
+
+    /// The most recently played event
+    pub fn last_event(&self) -> Option<&Event> {
+        match self.position {
+            0 => None,
+            n => self.log.events.get(n - 1),
+        }
+    }
+
+    /// Play the next event, returning false at the end of the game
+    pub fn forward(&mut self) -> bool {
+        if self.position < self.log.len() {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Take back the last event, returning false at the start of the game
+    pub fn back(&mut self) -> bool {
+        if self.position > 0 {
+            self.position -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Jump to just after the given number of events
+    pub fn seek(&mut self, position: usize) {
+        self.position = position.min(self.log.len());
+    }
+}
+
+#[test]
+fn replay_steps() {
+    let ace = Card::new(Value::Ace, Suit::Spades);
+    let two = Card::new(Value::Two, Suit::Hearts);
+
+    let mut log = EventLog::new();
+    log.push(Event::Started { players: vec!["a".into(), "b".into()], stock: 52 });
+    log.push(Event::Dealt { player: 0, cards: vec![ace] });
+    log.push(Event::Dealt { player: 1, cards: vec![two] });
+    log.push(Event::RoundWon { player: 0, cards: vec![ace, two] });
+    log.push(Event::GameOver { winner: Some(0) });
+
+    let mut replay = Replay::new(log);
+    while replay.forward() {}
+    assert_eq!(replay.snapshot().hands[0].len(), 2);
+    assert!(replay.snapshot().hands[1].is_empty());
+    assert_eq!(replay.snapshot().stock, 50);
+    assert_eq!(replay.snapshot().winner, Some(0));
+
+    replay.back();
+    replay.back();
+    assert_eq!(replay.snapshot().hands[1].len(), 1);
+    assert_eq!(replay.last_event(), Some(&Event::Dealt { player: 1, cards: vec![two] }));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn event_jsonl() -> io::Result<()> {
+    let mut log = EventLog::new();
+    log.push(Event::Asked { player: 0, target: 1, value: Value::Seven });
+    log.push(Event::WentFishing { player: 0, card: Card::new(Value::Ten, Suit::Clubs) });
+
+    let mut out = Vec::new();
+    log.write_jsonl(&mut out)?;
+    assert_eq!(
+        String::from_utf8(out.clone()).expect("utf8"),
+        "{\"event\":\"Asked\",\"player\":0,\"target\":1,\"value\":\"7\"}\n\
+         {\"event\":\"WentFishing\",\"player\":0,\"card\":\"TC\"}\n"
+    );
+    assert_eq!(EventLog::read_jsonl(&out[..])?, log);
+
+    Ok(())
+}
@@ -1,30 +1,33 @@
 use super::*;
-use DealError;
 
+/// Reading a game back in checks that no card is in two places, see
+/// `serialize`
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Game {
     pub deck: Deck,
     pub hands: Vec<Hand>,
+    /// Cards dealt aside from the hands, like a skat or a widow
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kitty: Hand,
 }
 
 impl Game {
+    /// Deal a shuffled deck one card at a time, starting with the first hand
     pub fn new(n_hands: usize, hand_size: usize) -> Result<Self, DealError> {
-        let mut deck = Deck::new();
-        let mut hands = Vec::new();
-
-        for _hand in 0..n_hands {
-            let mut hand = Hand::new();
-
-            for _card in 0..hand_size {
-                deck.deal(0, &mut hand)?;
-            }
+        Game::with_pattern(Deck::new(), &DealPattern::round_robin(n_hands, hand_size))
+    }
 
-            hands.push(hand);
-        }
+    /// Deal this deck the way the pattern says
+    pub fn with_pattern(mut deck: Deck, pattern: &DealPattern) -> DealResult<Self> {
+        let mut hands = vec![Hand::new(); pattern.hand_sizes.len()];
+        let mut kitty = Hand::new();
+        pattern.deal(&mut deck, &mut hands, &mut kitty)?;
 
         Ok(Game {
             deck,
             hands,
+            kitty,
         })
     }
 
@@ -0,0 +1,532 @@
+//! Texas Hold'em: blinds, betting rounds, side pots and the showdown
+
+use super::*;
+use std::fmt;
+
+/// How much a player may bet
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Betting {
+    /// Any amount up to the whole stack
+    NoLimit,
+    /// One big blind before the turn and two after, at most four bets a round
+    Limit,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Blinds {
+    pub small: u64,
+    pub big: u64,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+    /// The hand is over and the pots are paid
+    Done,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    /// Bet or raise so the total bet this round is this much
+    RaiseTo(u64),
+    AllIn,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::Fold => write!(f, "folds"),
+            Action::Check => write!(f, "checks"),
+            Action::Call => write!(f, "calls"),
+            Action::RaiseTo(to) => write!(f, "raises to {}", to),
+            Action::AllIn => write!(f, "is all in"),
+        }
+    }
+}
+
+/// Where one seat's chips are in the current hand
+#[derive(Debug, Clone, Default)]
+pub struct Chips {
+    /// Chips behind, not yet bet
+    pub stack: u64,
+    /// Bet this round
+    pub bet: u64,
+    /// Bet this hand, counting this round
+    pub committed: u64,
+    pub folded: bool,
+    /// Has acted since the last full raise
+    pub acted: bool,
+    /// Won at the end of the last hand
+    pub won: u64,
+}
+
+impl Chips {
+    pub fn new(stack: u64) -> Self {
+        Chips { stack, folded: true, ..Chips::default() }
+    }
+
+    /// Still holding cards in this hand
+    pub fn in_hand(&self) -> bool {
+        !self.folded
+    }
+
+    pub fn all_in(&self) -> bool {
+        self.in_hand() && self.stack == 0
+    }
+
+    fn put_in(&mut self, amount: u64) {
+        let amount = amount.min(self.stack);
+        self.stack -= amount;
+        self.bet += amount;
+        self.committed += amount;
+    }
+}
+
+/// Chips that go to the best hand among the players eligible for them
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Pot {
+    pub amount: u64,
+    pub eligible: Vec<usize>,
+}
+
+/// What the player to act may do
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Options {
+    /// Zero means a check
+    pub call: u64,
+    /// The smallest and largest total bets a raise may make, if any
+    pub raise: Option<(u64, u64)>,
+}
+
+/// A Hold'em table, playing one hand at a time
+///
+/// The cards are in the table's zones: the deck in the "stock", each
+/// player's hole cards in their "hand", the community cards on the "board"
+/// and burned and folded cards in the "discard". The dealer is the button.
+#[derive(Debug, Clone)]
+pub struct Holdem {
+    pub betting: Betting,
+    pub blinds: Blinds,
+    pub table: Table,
+    /// Each seat's chips
+    pub chips: Vec<Chips>,
+    pub street: Street,
+    pub to_act: Option<usize>,
+    /// The bet to match this round
+    pub current_bet: u64,
+    /// The least a raise must add to the current bet
+    pub min_raise: u64,
+    /// Bets and raises this round, counting the big blind
+    raises: usize,
+}
+
+/// The most bets in a round of limit betting
+const LIMIT_CAP: usize = 4;
+
+impl Holdem {
+    /// A table of players with the same number of chips each
+    pub fn new(betting: Betting, blinds: Blinds, names: &[&str], stack: u64) -> Self {
+        let mut table = Table::with_deck(names, Deck::default());
+        table.add_zone("board");
+        Holdem {
+            betting,
+            blinds,
+            table,
+            chips: vec![Chips::new(stack); names.len()],
+            street: Street::Done,
+            to_act: None,
+            current_bet: 0,
+            min_raise: blinds.big,
+            raises: 0,
+        }
+    }
+
+    /// The seat with the dealer button
+    pub fn button(&self) -> usize {
+        self.table.dealer
+    }
+
+    pub fn name(&self, seat: usize) -> &str {
+        &self.table.seats[seat].name
+    }
+
+    pub fn hole(&self, seat: usize) -> &Hand {
+        self.zone((seat, "hand"))
+    }
+
+    pub fn board(&self) -> &Hand {
+        self.zone("board")
+    }
+
+    /// Every chip at the table, in stacks and in the pot
+    pub fn total_chips(&self) -> u64 {
+        self.chips.iter().map(|c| c.stack + c.committed).sum()
+    }
+
+    /// The seats that still have chips
+    pub fn players_left(&self) -> usize {
+        self.chips.iter().filter(|c| c.stack > 0).count()
+    }
+
+    /// Move the button to the next player with chips and deal
+    pub fn next_hand(&mut self, deck: Deck) -> Result<(), HoldemError> {
+        self.table.dealer = self.next_seat(self.button(), |c| c.stack > 0).ok_or(HoldemError::NotEnoughPlayers)?;
+        self.start_hand(deck)
+    }
+
+    /// Post the blinds and deal two cards to each player with chips,
+    /// starting left of the button
+    pub fn start_hand(&mut self, deck: Deck) -> Result<(), HoldemError> {
+        if self.players_left() < 2 {
+            return Err(HoldemError::NotEnoughPlayers);
+        }
+        for chips in self.chips.iter_mut() {
+            *chips = Chips { stack: chips.stack, folded: chips.stack == 0, ..Chips::default() };
+        }
+        if self.chips[self.button()].stack == 0 {
+            self.table.dealer = self.next_seat(self.button(), |c| c.stack > 0).expect("two players");
+        }
+        let zones: Vec<ZoneId> = self.table.zones().map(|(id, _)| id).collect();
+        for id in zones {
+            self.table.zone_mut(id).expect("zone").cards = Hand::new();
+        }
+        self.table.zone_mut("stock").expect("stock").cards = Hand::from(deck.cards);
+        self.street = Street::Preflop;
+
+        // Heads up, the button posts the small blind
+        let small = if self.players_left() == 2 {
+            self.button()
+        } else {
+            self.next_seat(self.button(), Chips::in_hand).expect("two players")
+        };
+        let big = self.next_seat(small, Chips::in_hand).expect("two players");
+        self.chips[small].put_in(self.blinds.small);
+        self.chips[big].put_in(self.blinds.big);
+        self.current_bet = self.blinds.big;
+        self.min_raise = self.blinds.big;
+        self.raises = 1;
+
+        let first = self.next_seat(self.button(), Chips::in_hand).expect("two players");
+        for _ in 0..2 {
+            let mut seat = first;
+            loop {
+                self.table.move_cards("stock", 1, (seat, "hand"))?;
+                seat = self.next_seat(seat, Chips::in_hand).expect("two players");
+                if seat == first {
+                    break;
+                }
+            }
+        }
+
+        self.to_act = self.next_actor(big);
+        if self.to_act.is_none() {
+            self.end_round()?;
+        }
+        Ok(())
+    }
+
+    fn zone<K: ZoneKey>(&self, key: K) -> &Hand {
+        self.table.cards(key).expect("Hold'em zone")
+    }
+
+    /// The next seat after `from`, going around the table, that passes
+    fn next_seat<F: Fn(&Chips) -> bool>(&self, from: usize, pass: F) -> Option<usize> {
+        (1..=self.chips.len())
+            .filter_map(|i| self.table.left_of(from, i))
+            .find(|i| pass(&self.chips[*i]))
+    }
+
+    /// Who acts after `from`, or `None` when the round is over
+    fn next_actor(&self, from: usize) -> Option<usize> {
+        let live: Vec<&Chips> = self.chips.iter().filter(|c| c.in_hand()).collect();
+        let with_chips: Vec<&&Chips> = live.iter().filter(|c| c.stack > 0).collect();
+        if live.len() < 2 {
+            return None;
+        }
+        if let [only] = with_chips.as_slice() {
+            if only.bet >= self.current_bet {
+                return None;
+            }
+        }
+        self.next_seat(from, |c| c.in_hand() && c.stack > 0 && (!c.acted || c.bet < self.current_bet))
+    }
+
+    /// What the player to act may do
+    pub fn options(&self) -> Option<Options> {
+        let chips = &self.chips[self.to_act?];
+        let call = (self.current_bet - chips.bet).min(chips.stack);
+        let most = chips.bet + chips.stack;
+        let reopened = !chips.acted;
+        // Nobody could call a raise if everyone else is all in
+        let callers = self.chips.iter().filter(|c| c.in_hand() && c.stack > 0).count() > 1;
+        let raise = match self.betting {
+            _ if most <= self.current_bet || !reopened || !callers => None,
+            Betting::NoLimit => Some(((self.current_bet + self.min_raise).min(most), most)),
+            Betting::Limit if self.raises >= LIMIT_CAP => None,
+            Betting::Limit => {
+                let to = (self.current_bet + self.limit_size()).min(most);
+                Some((to, to))
+            }
+        };
+        Some(Options { call, raise })
+    }
+
+    /// The size of a bet in limit: the big blind before the turn and
+    /// twice that after
+    fn limit_size(&self) -> u64 {
+        match self.street {
+            Street::Preflop | Street::Flop => self.blinds.big,
+            _ => 2 * self.blinds.big,
+        }
+    }
+
+    /// The player to act does something
+    pub fn act(&mut self, action: Action) -> Result<(), HoldemError> {
+        let seat = self.to_act.ok_or(HoldemError::NobodyToAct)?;
+        let options = self.options().ok_or(HoldemError::NobodyToAct)?;
+        let most = self.chips[seat].bet + self.chips[seat].stack;
+
+        let action = match action {
+            // In limit the biggest raise allowed is as all in as it gets
+            Action::AllIn if most > self.current_bet && options.raise.is_some() => {
+                Action::RaiseTo(options.raise.map_or(most, |(_, max)| max))
+            }
+            Action::AllIn => Action::Call,
+            Action::Call if options.call == 0 => Action::Check,
+            other => other,
+        };
+
+        match action {
+            Action::Fold => {
+                self.chips[seat].folded = true;
+                let held = self.hole(seat).len();
+                self.table.move_cards((seat, "hand"), held, "discard")?;
+            }
+            Action::Check if options.call == 0 => {}
+            Action::Check => return Err(HoldemError::Illegal(action)),
+            Action::Call => self.chips[seat].put_in(options.call),
+            Action::RaiseTo(to) => {
+                let (least, most) = options.raise.ok_or(HoldemError::Illegal(action))?;
+                if to < least || to > most {
+                    return Err(HoldemError::Illegal(action));
+                }
+                let raise = to - self.current_bet;
+                if raise >= self.min_raise {
+                    // A full raise reopens the betting for everyone
+                    self.min_raise = raise;
+                    for other in self.chips.iter_mut() {
+                        other.acted = false;
+                    }
+                }
+                let put = to - self.chips[seat].bet;
+                self.chips[seat].put_in(put);
+                self.current_bet = to;
+                self.raises += 1;
+            }
+            Action::AllIn => unreachable!("all in is a call or a raise"),
+        }
+        self.chips[seat].acted = true;
+
+        self.to_act = self.next_actor(seat);
+        if self.to_act.is_none() {
+            self.end_round()?;
+        }
+        Ok(())
+    }
+
+    /// Gather the bets and deal the next street, running the board out
+    /// when nobody is left to bet
+    fn end_round(&mut self) -> Result<(), HoldemError> {
+        loop {
+            for chips in self.chips.iter_mut() {
+                chips.bet = 0;
+                chips.acted = false;
+            }
+            self.current_bet = 0;
+            self.min_raise = self.blinds.big;
+            self.raises = 0;
+
+            if self.chips.iter().filter(|c| c.in_hand()).count() < 2 || self.street == Street::River {
+                return self.showdown();
+            }
+
+            self.table.move_cards("stock", 1, "discard")?;
+            let (street, cards) = match self.street {
+                Street::Preflop => (Street::Flop, 3),
+                Street::Flop => (Street::Turn, 1),
+                _ => (Street::River, 1),
+            };
+            self.table.move_cards("stock", cards, "board")?;
+            self.street = street;
+
+            self.to_act = self.next_actor(self.button());
+            if self.to_act.is_some() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// The main pot and side pots, from the bets of everyone still in
+    pub fn pots(&self) -> Vec<Pot> {
+        let mut levels: Vec<u64> = self.chips.iter().filter(|c| c.in_hand()).map(|c| c.committed).collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut pots: Vec<Pot> = Vec::new();
+        let mut below = 0;
+        for level in levels {
+            let amount = self.chips.iter().map(|c| c.committed.min(level) - c.committed.min(below)).sum();
+            let eligible = (0..self.chips.len())
+                .filter(|i| self.chips[*i].in_hand() && self.chips[*i].committed >= level)
+                .collect();
+            below = level;
+            match pots.last_mut() {
+                // The same players share a pot, however many levels it spans
+                Some(pot) if pot.eligible == eligible => pot.amount += amount,
+                _ => pots.push(Pot { amount, eligible }),
+            }
+        }
+        pots
+    }
+
+    /// A player's best hand, once the board is out
+    pub fn strength(&self, seat: usize) -> Option<Strength> {
+        if !self.chips[seat].in_hand() {
+            return None;
+        }
+        evaluate(&[self.hole(seat).cards.as_slice(), self.board().cards.as_slice()].concat())
+    }
+
+    /// Pay each pot to its best hands, splitting ties with the odd chips
+    /// going first to the left of the button
+    fn showdown(&mut self) -> Result<(), HoldemError> {
+        let n = self.chips.len();
+        let button = self.button();
+        for pot in self.pots() {
+            let strengths: Vec<Option<Strength>> = pot.eligible.iter().map(|i| self.strength(*i)).collect();
+            let best = strengths.iter().flatten().max().copied();
+            let mut winners: Vec<usize> = pot.eligible
+                .iter()
+                .zip(strengths.iter())
+                .filter(|(_, s)| strengths.len() == 1 || **s == best)
+                .map(|(i, _)| *i)
+                .collect();
+            winners.sort_by_key(|i| (i + n - button - 1) % n);
+
+            let share = pot.amount / winners.len() as u64;
+            let odd = (pot.amount % winners.len() as u64) as usize;
+            for (k, winner) in winners.iter().enumerate() {
+                let won = share + u64::from(k < odd);
+                self.chips[*winner].stack += won;
+                self.chips[*winner].won += won;
+            }
+        }
+
+        for chips in self.chips.iter_mut() {
+            chips.committed = 0;
+        }
+        self.street = Street::Done;
+        self.to_act = None;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HoldemError {
+    NotEnoughPlayers,
+    NobodyToAct,
+    Illegal(Action),
+    Deal(DealError),
+}
+
+impl From<DealError> for HoldemError {
+    fn from(e: DealError) -> Self {
+        HoldemError::Deal(e)
+    }
+}
+
+impl fmt::Display for HoldemError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HoldemError::NotEnoughPlayers => write!(f, "Hold'em needs two players with chips"),
+            HoldemError::NobodyToAct => write!(f, "Nobody is due to act"),
+            HoldemError::Illegal(action) => write!(f, "Can't do that: {}", action),
+            HoldemError::Deal(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for HoldemError {}
+
+#[test]
+fn holdem_betting() -> Result<(), HoldemError> {
+    let stacked = |top: &str| {
+        let mut cards = parse_cards(top).expect("cards");
+        let rest: Vec<Card> = Deck::default().cards.into_iter().filter(|c| !cards.contains(c)).collect();
+        cards.extend(rest);
+        Deck::from(cards)
+    };
+    let blinds = Blinds { small: 5, big: 10 };
+    let names = ["P0", "P1", "P2"];
+
+    // Three all in: aces win the main pot, kings the side pot
+    let mut game = Holdem::new(Betting::NoLimit, blinds, &names, 300);
+    game.chips[0].stack = 100;
+    game.table.dealer = 2;
+    game.start_hand(stacked("AS KS QS AH KH QH 2C 7D 8C 3D 4S 9H 5S JC"))?;
+    assert_eq!(game.to_act, Some(2));
+    game.act(Action::AllIn)?;
+    game.act(Action::AllIn)?;
+    assert_eq!(game.pots(), vec![
+        Pot { amount: 30, eligible: vec![0, 1, 2] },
+        Pot { amount: 180, eligible: vec![0, 2] },
+        Pot { amount: 200, eligible: vec![2] },
+    ]);
+    assert_eq!(game.options(), Some(Options { call: 290, raise: None }));
+    game.act(Action::Call)?;
+    assert_eq!(game.street, Street::Done);
+    assert_eq!(game.board().len(), 5);
+    assert_eq!(game.chips.iter().map(|c| c.stack).collect::<Vec<_>>(), vec![300, 400, 0]);
+    assert_eq!(game.total_chips(), 700);
+    assert_eq!(game.players_left(), 2);
+
+    // Heads up the button posts the small blind and a raise must be at
+    // least as big as the last one
+    let mut game = Holdem::new(Betting::NoLimit, blinds, &names[..2], 500);
+    game.start_hand(Deck::default())?;
+    assert_eq!((game.to_act, game.chips[0].bet), (Some(0), 5));
+    assert_eq!(game.act(Action::RaiseTo(15)), Err(HoldemError::Illegal(Action::RaiseTo(15))));
+    assert_eq!(HoldemError::Illegal(Action::RaiseTo(15)).to_string(), "Can't do that: raises to 15");
+    game.act(Action::RaiseTo(30))?;
+    assert_eq!(game.options(), Some(Options { call: 20, raise: Some((50, 500)) }));
+    game.act(Action::Call)?;
+    assert_eq!((game.street, game.to_act), (Street::Flop, Some(1)));
+    assert_eq!(game.act(Action::Call), Ok(()));
+    game.act(Action::RaiseTo(40))?;
+    game.act(Action::Fold)?;
+    assert_eq!(game.street, Street::Done);
+    assert_eq!((game.chips[0].stack, game.chips[1].stack), (530, 470));
+    assert!(Audit::default().check(&game.table.census()).is_ok());
+
+    // Limit raises are fixed and capped
+    let mut game = Holdem::new(Betting::Limit, blinds, &names, 500);
+    game.start_hand(Deck::default())?;
+    assert_eq!(game.options(), Some(Options { call: 10, raise: Some((20, 20)) }));
+    game.act(Action::AllIn)?;
+    assert_eq!(game.current_bet, 20);
+    game.act(Action::RaiseTo(30))?;
+    game.act(Action::RaiseTo(40))?;
+    assert_eq!(game.options(), Some(Options { call: 20, raise: None }));
+    game.act(Action::Call)?;
+    game.act(Action::Call)?;
+    assert_eq!(game.street, Street::Flop);
+    assert_eq!(game.total_chips(), 1500);
+
+    Ok(())
+}
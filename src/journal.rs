@@ -0,0 +1,234 @@
+//! A journal of card moves that can be undone and redone
+
+use super::*;
+
+/// A pile of cards whose moves can be taken back
+pub trait Pile: Give<Item = Card> + Take<Item = Card> {
+    /// Put a card back where it was given from
+    fn insert(&mut self, index: usize, card: Card);
+
+    /// Remove the card that was taken last
+    fn untake(&mut self) -> Option<Card>;
+}
+
+impl Pile for Deck {
+    fn insert(&mut self, index: usize, card: Card) {
+        self.cards.insert(index, card);
+    }
+
+    fn untake(&mut self) -> Option<Card> {
+        self.cards.pop()
+    }
+}
+
+impl Pile for Hand {
+    fn insert(&mut self, index: usize, card: Card) {
+        self.cards.insert(index, card);
+    }
+
+    fn untake(&mut self) -> Option<Card> {
+        self.cards.pop()
+    }
+}
+
+/// Numbered piles that cards move between
+pub trait Piles {
+    fn pile(&mut self, id: usize) -> Option<&mut dyn Pile>;
+}
+
+/// The deck is pile 0, each hand follows it and the kitty comes last
+impl Piles for Game {
+    fn pile(&mut self, id: usize) -> Option<&mut dyn Pile> {
+        let kitty = self.hands.len() + 1;
+        match id {
+            0 => Some(&mut self.deck),
+            n if n == kitty => Some(&mut self.kitty),
+            n => self.hands.get_mut(n - 1).map(|h| h as &mut dyn Pile),
+        }
+    }
+}
+
+impl Piles for [Hand] {
+    fn pile(&mut self, id: usize) -> Option<&mut dyn Pile> {
+        self.get_mut(id).map(|h| h as &mut dyn Pile)
+    }
+}
+
+impl Piles for Vec<Hand> {
+    fn pile(&mut self, id: usize) -> Option<&mut dyn Pile> {
+        self.as_mut_slice().pile(id)
+    }
+}
+
+/// One card given from a pile and taken by another
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Move {
+    pub from: usize,
+    /// Where the card was in the `from` pile
+    pub index: usize,
+    pub to: usize,
+    pub card: Card,
+}
+
+/// A point in the journal to roll back to
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Checkpoint(usize);
+
+/// Every move made, so they can be undone and redone
+///
+/// Undoing relies on the piles only changing through the journal: a card
+/// is undone by taking the last card off the pile it went to.
+#[derive(Debug, Clone, Default)]
+pub struct Journal {
+    done: Vec<Move>,
+    undone: Vec<Move>,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Journal::default()
+    }
+
+    /// The moves made so far, oldest first
+    pub fn moves(&self) -> &[Move] {
+        &self.done
+    }
+
+    /// Deal the card at `index` of pile `from` to pile `to`
+    ///
+    /// A new move can't be redone past, so it clears the redo history.
+    pub fn deal<P: Piles + ?Sized>(
+        &mut self,
+        piles: &mut P,
+        from: usize,
+        index: usize,
+        to: usize,
+    ) -> DealResult<Card> {
+        piles.pile(to).ok_or(DealError::OutOfBounds)?;
+        let card = piles.pile(from).ok_or(DealError::OutOfBounds)?.give(index)?;
+        piles.pile(to).ok_or(DealError::OutOfBounds)?.take(card);
+
+        self.done.push(Move { from, index, to, card });
+        self.undone.clear();
+        Ok(card)
+    }
+
+    /// Take back the last move
+    pub fn undo<P: Piles + ?Sized>(&mut self, piles: &mut P) -> DealResult<Option<Move>> {
+        let m = match self.done.last() {
+            Some(m) => *m,
+            None => return Ok(None),
+        };
+
+        reverse(piles, &m)?;
+        self.done.pop();
+        self.undone.push(m);
+        Ok(Some(m))
+    }
+
+    /// Make the last undone move again
+    pub fn redo<P: Piles + ?Sized>(&mut self, piles: &mut P) -> DealResult<Option<Move>> {
+        let m = match self.undone.pop() {
+            Some(m) => m,
+            None => return Ok(None),
+        };
+
+        let card = piles.pile(m.from).ok_or(DealError::OutOfBounds)?.give(m.index)?;
+        piles.pile(m.to).ok_or(DealError::OutOfBounds)?.take(card);
+        self.done.push(m);
+        Ok(Some(m))
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.done.len())
+    }
+
+    /// Undo every move since the checkpoint, and forget them
+    pub fn rollback<P: Piles + ?Sized>(&mut self, piles: &mut P, checkpoint: Checkpoint) -> DealResult<()> {
+        while self.done.len() > checkpoint.0 {
+            if let Some(m) = self.done.last() {
+                // Only forget the move once its card is back
+                reverse(piles, m)?;
+                self.done.pop();
+            }
+        }
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// Make several moves as one: if any fails, none of them happen
+    pub fn transaction<P, T, F>(&mut self, piles: &mut P, f: F) -> DealResult<T>
+    where
+        P: Piles + ?Sized,
+        F: FnOnce(&mut Journal, &mut P) -> DealResult<T>,
+    {
+        let checkpoint = self.checkpoint();
+        match f(self, piles) {
+            Ok(t) => Ok(t),
+            Err(e) => {
+                self.rollback(piles, checkpoint)?;
+                Err(e)
+            }
+        }
+    }
+}
+
+fn reverse<P: Piles + ?Sized>(piles: &mut P, m: &Move) -> DealResult<()> {
+    piles.pile(m.from).ok_or(DealError::OutOfBounds)?;
+    let card = piles.pile(m.to).ok_or(DealError::OutOfBounds)?
+        .untake()
+        .ok_or(DealError::NothingToGive)?;
+    debug_assert_eq!(card, m.card, "a pile changed outside the journal");
+    piles.pile(m.from).ok_or(DealError::OutOfBounds)?.insert(m.index, card);
+    Ok(())
+}
+
+#[test]
+fn undo_redo_rollback() -> DealResult<()> {
+    let mut game = Game::new(2, 2)?;
+    let original = (game.deck.clone(), game.hands.clone());
+    let mut journal = Journal::new();
+
+    journal.deal(&mut game, 0, 0, 1)?;
+    journal.deal(&mut game, 2, 1, 1)?;
+    assert_eq!(game.hands[0].len(), 4);
+
+    journal.undo(&mut game)?;
+    journal.undo(&mut game)?;
+    assert_eq!((game.deck.clone(), game.hands.clone()), original);
+
+    journal.redo(&mut game)?;
+    assert_eq!(game.hands[0].len(), 3);
+    assert_eq!(journal.moves().len(), 1);
+
+    // A war battle needs four cards from each hand, but hand 1 only has two
+    let before = (game.deck.clone(), game.hands.clone());
+    let battle = journal.transaction(&mut game, |journal, game| {
+        for _ in 0..4 {
+            journal.deal(game, 1, 0, 0)?;
+            journal.deal(game, 2, 0, 0)?;
+        }
+        Ok(())
+    });
+    assert!(battle.is_err());
+    assert_eq!((game.deck.clone(), game.hands.clone()), before);
+    assert_eq!(journal.moves().len(), 1);
+
+    // The kitty is the pile after the last hand
+    let checkpoint = journal.checkpoint();
+    journal.deal(&mut game, 0, 0, 3)?;
+    assert_eq!(game.kitty.len(), 1);
+    journal.undo(&mut game)?;
+    assert!(game.kitty.is_empty());
+    journal.redo(&mut game)?;
+
+    // A move that can't be taken back stays in the journal
+    let lost = game.kitty.drain();
+    assert!(journal.rollback(&mut game, checkpoint).is_err());
+    assert_eq!(journal.moves().len(), 2);
+    game.kitty = lost;
+    journal.rollback(&mut game, checkpoint)?;
+    assert_eq!(journal.moves().len(), 1);
+
+    Ok(())
+}
@@ -1,20 +1,70 @@
 use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::io;
+use std::str::FromStr;
 
+mod art;
+mod audit;
+mod constraint;
 mod game;
+mod holdem;
+mod journal;
+mod locale;
+mod lowball;
+mod magic;
+mod mental;
+mod omaha;
+mod permutation;
+mod poker;
+mod range;
 mod deal;
+mod dealing;
+mod equity;
+mod event;
 mod display;
 mod cards;
 mod shuffle;
-
+mod stats;
+#[cfg(feature = "secure")]
+mod secure;
+#[cfg(feature = "serde")]
+mod serialize;
+mod svg;
+mod table;
+mod turn;
+pub mod tui;
+
+pub use art::*;
+pub use audit::*;
+pub use constraint::*;
 pub use crate::cards::*;
 pub use deal::*;
+pub use dealing::*;
+pub use equity::*;
+pub use event::*;
 pub use game::*;
+pub use holdem::*;
+pub use journal::*;
+#[cfg(feature = "secure")]
+pub use secure::*;
+#[cfg(feature = "serde")]
+pub use serialize::*;
+pub use locale::*;
+pub use lowball::*;
+pub use magic::*;
+pub use mental::*;
+pub use omaha::*;
+pub use permutation::*;
+pub use poker::*;
+pub use range::*;
 pub use shuffle::*;
+pub use stats::*;
+pub use svg::*;
+pub use table::*;
+pub use turn::*;
 
 /// The playing card
-#[derive(Copy, Clone, Hash)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Card {
     pub value: Value,
     pub suit: Suit,
@@ -45,14 +95,6 @@ impl Card {
     }
 }
 
-impl PartialEq for Card {
-    fn eq(&self, other: &Self) -> bool {
-        self.value == other.value && self.suit == other.suit
-    }
-}
-
-impl Eq for Card {}
-
 impl Ord for Card {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         self.cmp_value().cmp(&other.cmp_value())
@@ -65,6 +107,20 @@ impl PartialOrd for Card {
     }
 }
 
+/// Parse a value followed by a suit: "AS", "10♥", "td"
+impl FromStr for Card {
+    type Err = io::Error;
+    fn from_str(s: &str) -> Result<Card, Self::Err> {
+        let split = match s.char_indices().last() {
+            Some((i, _)) => i,
+            None => return Err(io::Error::from(io::ErrorKind::InvalidInput)),
+        };
+        let (value, suit) = s.split_at(split);
+
+        Ok(Card::new(Value::try_from(value)?, Suit::try_from(suit)?))
+    }
+}
+
 impl Default for Card {
     fn default() -> Self {
         Card {
@@ -92,6 +148,23 @@ pub enum Value {
     Ace,
 }
 
+/// Every value from low to high
+pub const VALUES: [Value; 13] = [
+    Value::Two,
+    Value::Three,
+    Value::Four,
+    Value::Five,
+    Value::Six,
+    Value::Seven,
+    Value::Eight,
+    Value::Nine,
+    Value::Ten,
+    Value::Jack,
+    Value::Queen,
+    Value::King,
+    Value::Ace,
+];
+
 impl From<u8> for Value {
     fn from(u: u8) -> Value {
         match u {
@@ -151,6 +224,24 @@ pub enum Suit {
     Spades,
 }
 
+/// Every suit in order
+pub const SUITS: [Suit; 4] = [Suit::Diamonds, Suit::Clubs, Suit::Hearts, Suit::Spades];
+
+impl TryFrom<&str> for Suit {
+    type Error = io::Error;
+    fn try_from(s: &str) -> Result<Suit, Self::Error> {
+        let suit = match s {
+            "D"|"d"|"♦" => Suit::Diamonds,
+            "C"|"c"|"♣" => Suit::Clubs,
+            "H"|"h"|"♥" => Suit::Hearts,
+            "S"|"s"|"♠" => Suit::Spades,
+            _ => return Err(io::Error::from(io::ErrorKind::InvalidInput)),
+        };
+
+        Ok(suit)
+    }
+}
+
 #[test]
 fn suit_ord() {
     use Suit::*;
@@ -206,7 +297,7 @@ impl Deck {
 }
 
 /// A hand of playing cards
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct Hand {
     cards: Vec<Card>,
 }
@@ -235,6 +326,42 @@ impl Hand {
     }
 }
 
+impl From<Vec<Card>> for Hand {
+    fn from(cards: Vec<Card>) -> Self {
+        Hand { cards }
+    }
+}
+
+/// A deck in exactly this order, top card first
+impl From<Vec<Card>> for Deck {
+    fn from(cards: Vec<Card>) -> Self {
+        Deck { cards }
+    }
+}
+
+/// Parse cards separated by spaces or commas: "AS, KD 10♥"
+pub fn parse_cards(s: &str) -> io::Result<Vec<Card>> {
+    s.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|code| !code.is_empty())
+        .map(Card::from_str)
+        .collect()
+}
+
+/// A stacked deck, top card first
+impl FromStr for Deck {
+    type Err = io::Error;
+    fn from_str(s: &str) -> Result<Deck, Self::Err> {
+        parse_cards(s).map(Deck::from)
+    }
+}
+
+impl FromStr for Hand {
+    type Err = io::Error;
+    fn from_str(s: &str) -> Result<Hand, Self::Err> {
+        parse_cards(s).map(Hand::from)
+    }
+}
+
 impl IntoIterator for Hand {
     type Item = Card;
     type IntoIter = std::vec::IntoIter<Self::Item>;
@@ -256,6 +383,7 @@ impl PartialEq for Hand {
 impl Eq for Hand {}
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub  struct MatchIndex<T> {
     pub matches: BTreeMap<usize, T>,
 }
@@ -0,0 +1,222 @@
+//! Card and suit names in several languages, and parsing of spoken values
+
+use super::*;
+use std::convert::TryFrom;
+
+/// A language that cards can be named in
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+    French,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::English,
+        Language::Spanish,
+        Language::French,
+        Language::German,
+    ];
+
+    /// Look up a language by its two letter code: "en", "es", "fr" or "de"
+    pub fn from_code(code: &str) -> Option<Language> {
+        match code.to_lowercase().as_str() {
+            "en" => Some(Language::English),
+            "es" => Some(Language::Spanish),
+            "fr" => Some(Language::French),
+            "de" => Some(Language::German),
+            _ => None,
+        }
+    }
+
+    /// The name of one card of this value, e.g. "Queen"
+    pub fn value_name(self, value: Value) -> &'static str {
+        self.names(value)[0]
+    }
+
+    /// The name of several cards of this value, e.g. "Queens"
+    pub fn value_plural(self, value: Value) -> &'static str {
+        self.names(value)[1]
+    }
+
+    /// The name of a suit, e.g. "Hearts"
+    pub fn suit_name(self, suit: Suit) -> &'static str {
+        use Suit::*;
+        match self {
+            Language::English => match suit {
+                Diamonds => "Diamonds",
+                Clubs => "Clubs",
+                Hearts => "Hearts",
+                Spades => "Spades",
+            },
+            Language::Spanish => match suit {
+                Diamonds => "diamantes",
+                Clubs => "tréboles",
+                Hearts => "corazones",
+                Spades => "picas",
+            },
+            Language::French => match suit {
+                Diamonds => "carreau",
+                Clubs => "trèfle",
+                Hearts => "cœur",
+                Spades => "pique",
+            },
+            Language::German => match suit {
+                Diamonds => "Karo",
+                Clubs => "Kreuz",
+                Hearts => "Herz",
+                Spades => "Pik",
+            },
+        }
+    }
+
+    /// The full name of a card, e.g. "Queen of Hearts"
+    pub fn card_name(self, card: &Card) -> String {
+        let value = self.value_name(card.value);
+        let suit = self.suit_name(card.suit);
+
+        match self {
+            Language::English => format!("{} of {}", value, suit),
+            Language::Spanish | Language::French => format!("{} de {}", value, suit),
+            Language::German => format!("{}-{}", suit, value),
+        }
+    }
+
+    /// Parse a single spoken value in this language, singular or plural
+    pub fn parse_value(self, word: &str) -> Option<Value> {
+        let word = word.to_lowercase();
+
+        VALUES.iter().copied().find(|value| {
+            self.names(*value)
+                .iter()
+                .any(|name| name.to_lowercase() == word)
+        })
+    }
+
+    /// Find the card value in a phrase in this language, like "do you have
+    /// any sevens?"
+    ///
+    /// A phrase of a single word can also be a short token like "Q" or "10".
+    /// In a longer phrase only spoken names and numbers count, so words like
+    /// "a" aren't mistaken for an ace.
+    pub fn phrase_value(self, phrase: &str) -> Option<Value> {
+        let words: Vec<&str> = phrase
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        if let [word] = words.as_slice() {
+            if let Ok(value) = Value::try_from(*word) {
+                return Some(value);
+            }
+        }
+
+        words.iter().rev().find_map(|word| {
+            self.parse_value(word).or_else(|| match word.parse::<u8>() {
+                Ok(n @ 2..=10) => Some(Value::from(n)),
+                _ => None,
+            })
+        })
+    }
+
+    /// Singular and plural names, then any other accepted spellings
+    fn names(self, value: Value) -> &'static [&'static str] {
+        use Value::*;
+        match self {
+            Language::English => match value {
+                Two => &["Two", "Twos", "Deuce", "Deuces"],
+                Three => &["Three", "Threes", "Trey", "Treys"],
+                Four => &["Four", "Fours"],
+                Five => &["Five", "Fives"],
+                Six => &["Six", "Sixes"],
+                Seven => &["Seven", "Sevens"],
+                Eight => &["Eight", "Eights"],
+                Nine => &["Nine", "Nines"],
+                Ten => &["Ten", "Tens"],
+                Jack => &["Jack", "Jacks", "Knave", "Knaves"],
+                Queen => &["Queen", "Queens"],
+                King => &["King", "Kings"],
+                Ace => &["Ace", "Aces"],
+            },
+            Language::Spanish => match value {
+                Two => &["dos", "doses"],
+                Three => &["tres", "treses"],
+                Four => &["cuatro", "cuatros"],
+                Five => &["cinco", "cincos"],
+                Six => &["seis", "seises"],
+                Seven => &["siete", "sietes"],
+                Eight => &["ocho", "ochos"],
+                Nine => &["nueve", "nueves"],
+                Ten => &["diez", "dieces"],
+                Jack => &["jota", "jotas", "sota", "sotas"],
+                Queen => &["reina", "reinas"],
+                King => &["rey", "reyes"],
+                Ace => &["as", "ases"],
+            },
+            Language::French => match value {
+                Two => &["deux", "deux"],
+                Three => &["trois", "trois"],
+                Four => &["quatre", "quatres"],
+                Five => &["cinq", "cinqs"],
+                Six => &["six", "six"],
+                Seven => &["sept", "septs"],
+                Eight => &["huit", "huits"],
+                Nine => &["neuf", "neufs"],
+                Ten => &["dix", "dix"],
+                Jack => &["valet", "valets"],
+                Queen => &["dame", "dames"],
+                King => &["roi", "rois"],
+                Ace => &["as", "as"],
+            },
+            Language::German => match value {
+                Two => &["Zwei", "Zweien"],
+                Three => &["Drei", "Dreien"],
+                Four => &["Vier", "Vieren"],
+                Five => &["Fünf", "Fünfen", "Fuenf", "Fuenfen"],
+                Six => &["Sechs", "Sechsen"],
+                Seven => &["Sieben", "Siebenen"],
+                Eight => &["Acht", "Achten"],
+                Nine => &["Neun", "Neunen"],
+                Ten => &["Zehn", "Zehnen"],
+                Jack => &["Bube", "Buben"],
+                Queen => &["Dame", "Damen"],
+                King => &["König", "Könige", "Koenig", "Koenige"],
+                Ace => &["Ass", "Asse", "As"],
+            },
+        }
+    }
+}
+
+impl Value {
+    /// Find the card value in an English phrase like "do you have any
+    /// sevens?"; see `Language::phrase_value` for other languages
+    pub fn from_phrase(phrase: &str) -> Option<Value> {
+        Language::default().phrase_value(phrase)
+    }
+}
+
+#[test]
+fn names_and_phrases() {
+    let card = Card::new(Value::Queen, Suit::Hearts);
+    assert_eq!(Language::English.card_name(&card), "Queen of Hearts");
+    assert_eq!(Language::Spanish.card_name(&card), "reina de corazones");
+    assert_eq!(Language::French.card_name(&card), "dame de cœur");
+    assert_eq!(Language::German.card_name(&card), "Herz-Dame");
+
+    assert_eq!(Value::from_phrase("Q"), Some(Value::Queen));
+    assert_eq!(Value::from_phrase("queens"), Some(Value::Queen));
+    assert_eq!(Language::French.phrase_value("dame"), Some(Value::Queen));
+    assert_eq!(Value::from_phrase("do you have any sevens?"), Some(Value::Seven));
+    assert_eq!(Value::from_phrase("do you have a 10"), Some(Value::Ten));
+    assert_eq!(Language::Spanish.phrase_value("¿Tienes algún rey?"), Some(Value::King));
+    assert_eq!(Language::German.phrase_value("Hast du Könige?"), Some(Value::King));
+    assert_eq!(Value::from_phrase("do you have a"), None);
+
+    // "as" is only an ace in Spanish and French
+    assert_eq!(Value::from_phrase("as many sevens as you have"), Some(Value::Seven));
+    assert_eq!(Value::from_phrase("as good as it gets"), None);
+    assert_eq!(Language::Spanish.phrase_value("¿Tienes un as?"), Some(Value::Ace));
+}
@@ -0,0 +1,153 @@
+//! Low poker hands: deuce-to-seven, ace-to-five and Badugi
+
+use super::*;
+
+/// How good a low hand is; a better low compares greater
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Low(u32);
+
+impl Low {
+    /// The strength as a number, for tables and hashing
+    pub fn value(self) -> u32 {
+        self.0
+    }
+}
+
+/// A card's rank with the ace below the two
+fn ace_low(card: &Card) -> u8 {
+    (card.value as u8 + 1) % 13
+}
+
+/// The best deuce-to-seven low in five to seven cards: aces are high, and
+/// straights and flushes count against the hand, so 7-5-4-3-2 is the nuts
+pub fn deuce_seven(cards: &[Card]) -> Option<Best<Low>> {
+    if !(5..=7).contains(&cards.len()) {
+        return None;
+    }
+    best_of(&choose(cards, 5), |five| Some(Low(!ace_high(five).value())))
+}
+
+/// Five cards as a high hand where the ace never plays low, so A-5-4-3-2
+/// is ace high rather than a straight
+fn ace_high(five: &[Card]) -> Strength {
+    let strength = evaluate_unchecked(five);
+    let wheel = [Value::Ace as u8, Value::Five as u8, Value::Four as u8, Value::Three as u8, Value::Two as u8];
+    match strength.category() {
+        Category::Straight if strength.ranks() == [Value::Five] => Strength::new(Category::HighCard, &wheel),
+        Category::StraightFlush if strength.ranks() == [Value::Five] => Strength::new(Category::Flush, &wheel),
+        _ => strength,
+    }
+}
+
+/// The best ace-to-five low in five to seven cards: aces are low and
+/// straights and flushes don't count, so A-2-3-4-5 is the nuts
+pub fn ace_five(cards: &[Card]) -> Option<Best<Low>> {
+    if !(5..=7).contains(&cards.len()) {
+        return None;
+    }
+    best_of(&choose(cards, 5), |five| Some(ace_five_low(five)))
+}
+
+/// The best ace-to-five low with no pair and nothing above an eight, if
+/// there is one
+pub fn eight_or_better(cards: &[Card]) -> Option<Best<Low>> {
+    if !(5..=7).contains(&cards.len()) {
+        return None;
+    }
+    best_of(&choose(cards, 5), qualified_low)
+}
+
+/// The five cards' ace-to-five low, if it is eight or better
+pub(crate) fn qualified_low(five: &[Card]) -> Option<Low> {
+    let mut ranks: Vec<u8> = five.iter().map(ace_low).collect();
+    ranks.sort_unstable();
+    ranks.dedup();
+    if ranks.len() == 5 && ranks[4] <= Value::Eight as u8 + 1 {
+        Some(ace_five_low(five))
+    } else {
+        None
+    }
+}
+
+/// Rank five cards like a high hand with the ace low and without straights
+/// or flushes, then turn it around so the lowest hand is best
+fn ace_five_low(five: &[Card]) -> Low {
+    let mut counts = [0u8; 13];
+    for card in five.iter() {
+        counts[ace_low(card) as usize] += 1;
+    }
+
+    // Bigger groups first, then higher ranks
+    let mut groups: Vec<(u8, u8)> = (0..13u8)
+        .filter(|r| counts[*r as usize] > 0)
+        .map(|r| (counts[r as usize], r))
+        .collect();
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+
+    let category = match (groups[0].0, groups.get(1).map_or(0, |g| g.0)) {
+        (4, _) => Category::FourOfAKind,
+        (3, 2) => Category::FullHouse,
+        (3, _) => Category::ThreeOfAKind,
+        (2, 2) => Category::TwoPair,
+        (2, _) => Category::Pair,
+        _ => Category::HighCard,
+    };
+    let ranks = groups.iter().fold(0, |acc, (_, rank)| acc << 4 | u32::from(*rank)) << (4 * (5 - groups.len()));
+    Low(!((category as u32) << 20 | ranks))
+}
+
+/// The best Badugi in four cards: the most cards of different suits and
+/// ranks, aces low, then the lowest of those
+///
+/// The cards kept may be fewer than four.
+pub fn badugi(cards: &[Card]) -> Option<Best<Low>> {
+    if cards.len() != 4 {
+        return None;
+    }
+    let subsets: Vec<Vec<Card>> = (1..=4).flat_map(|k| choose(cards, k)).collect();
+    best_of(&subsets, |kept| {
+        let mut ranks: Vec<u8> = kept.iter().map(ace_low).collect();
+        let mut suits: Vec<Suit> = kept.iter().map(|c| c.suit).collect();
+        ranks.sort_unstable_by(|a, b| b.cmp(a));
+        suits.sort_unstable();
+        suits.dedup();
+        if suits.len() < kept.len() || ranks.windows(2).any(|w| w[0] == w[1]) {
+            return None;
+        }
+        let packed = ranks.iter().fold(0, |acc, rank| acc << 4 | u32::from(*rank)) << (4 * (4 - ranks.len()));
+        Some(Low((kept.len() as u32) << 16 | (0xFFFF - packed)))
+    })
+}
+
+#[test]
+fn low_hands() {
+    let cards = |s: &str| parse_cards(s).expect("cards");
+    let strength = |f: fn(&[Card]) -> Option<Best<Low>>, s: &str| f(&cards(s)).expect("low").strength;
+
+    // Deuce-to-seven: aces are high, so the wheel is ace high and beats a pair
+    assert!(strength(deuce_seven, "7C 5D 4H 3S 2C") > strength(deuce_seven, "7C 6D 4H 3S 2C"));
+    assert!(strength(deuce_seven, "8C 5D 4H 3S 2C") > strength(deuce_seven, "AC 5D 4H 3S 2C"));
+    assert!(strength(deuce_seven, "AC 5D 4H 3S 2C") > strength(deuce_seven, "2C 2D 4H 5S 7C"));
+    assert!(strength(deuce_seven, "KC QD JH 9S 8C") > strength(deuce_seven, "AC 5D 4H 3S 2C"));
+    assert!(strength(deuce_seven, "AC 5D 4H 3S 2C") > strength(deuce_seven, "AC 5C 4C 3C 2C"));
+    assert!(strength(deuce_seven, "8C 6D 4H 3S 2C") > strength(deuce_seven, "7C 6C 4C 3C 2C"));
+    let best = deuce_seven(&cards("KC 7D 5D 4H 3S 2C 2D")).expect("seven cards");
+    assert_eq!(best.cards, cards("7D 5D 4H 3S 2C"));
+
+    // Ace-to-five: the wheel is the nuts, pairs are bad
+    assert!(strength(ace_five, "AC 2D 3H 4S 5C") > strength(ace_five, "AC 2C 3C 4C 6C"));
+    assert!(strength(ace_five, "KC QD JH TS 9C") > strength(ace_five, "AC AD 2H 3S 4C"));
+    assert!(strength(ace_five, "8C 6D 4H 3S 2C") > strength(ace_five, "8C 7D 3H 2S AC"));
+    assert!(eight_or_better(&cards("9C 6D 4H 3S 2C")).is_none());
+    assert!(eight_or_better(&cards("8C 8D 4H 3S 3C AH")).is_none());
+    let low = eight_or_better(&cards("8C 8D 4H 3S 2C AH KD")).expect("low");
+    assert_eq!(low.cards, cards("8C 4H 3S 2C AH"));
+
+    // Badugi: more cards first, then the lowest
+    let best = badugi(&cards("AC 2D 3H 4S")).expect("four cards");
+    assert_eq!(best.cards.len(), 4);
+    assert!(strength(badugi, "KC QD JH TS") > strength(badugi, "AC 2D 3H 4H"));
+    assert!(strength(badugi, "AC 2D 3H 5S") > strength(badugi, "AC 2D 4H 5S"));
+    assert_eq!(badugi(&cards("AC AD 3C 4S")).expect("four cards").cards, cards("AD 3C 4S"));
+    assert!(badugi(&cards("AC 2D 3H")).is_none());
+}
@@ -0,0 +1,205 @@
+//! Memorized stacks, and where their cards go as the deck is handled
+//!
+//! Positions count from 1 at the top, the way magicians number a stack.
+
+use super::*;
+use std::convert::TryFrom;
+
+/// A deck order that a magician knows by heart
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Stack {
+    /// Juan Tamariz's stack
+    Mnemonica,
+    /// Simon Aronson's stack
+    Aronson,
+    /// Each card three higher than the one above, suits in CHaSeD order
+    SiStebbins,
+    /// "Eight kings threatened to save ninety-five queens for one sick
+    /// knave", suits in CHaSeD order
+    EightKings,
+}
+
+const MNEMONICA: &str = "4C 2H 7D 3C 4H 6D AS 5H 9S 2S QH 3D QC 8H 6S 5S 9H KC 2D JH 3S 8S 6H TC 5D KD \
+                         2C 3H 8D 5C KS JD 8C TS KH JC 7S TH AD 4S 7H 4D AC 9C JS QD 7C QS TD 6C AH 9D";
+
+const ARONSON: &str = "JS KC 5C 2H 9S AS 3H 6C 8D AC TS 5H 2D KD 7D 8C 3S AD 7S 5S QD AH 8S 3D 7H QH \
+                       5D 7C 4H KH 4D TD JC JH TC JD 4S TH 6H 3C 2S 9H KS 6S 4C 8H 9C QS 6D QC 2C 9D";
+
+/// Clubs, Hearts, Spades, Diamonds
+const CHASED: [Suit; 4] = [Suit::Clubs, Suit::Hearts, Suit::Spades, Suit::Diamonds];
+
+const EIGHT_KINGS: [Value; 13] = [
+    Value::Eight,
+    Value::King,
+    Value::Three,
+    Value::Ten,
+    Value::Two,
+    Value::Seven,
+    Value::Nine,
+    Value::Five,
+    Value::Queen,
+    Value::Four,
+    Value::Ace,
+    Value::Six,
+    Value::Jack,
+];
+
+impl Stack {
+    pub const ALL: [Stack; 4] = [Stack::Mnemonica, Stack::Aronson, Stack::SiStebbins, Stack::EightKings];
+
+    /// Every card in the stack, top first
+    pub fn cards(self) -> Vec<Card> {
+        match self {
+            Stack::Mnemonica => parse_cards(MNEMONICA).expect("Mnemonica"),
+            Stack::Aronson => parse_cards(ARONSON).expect("Aronson"),
+            Stack::SiStebbins => (0..52)
+                .map(|i| {
+                    let value = VALUES[(12 + 3 * i) % 13];
+                    Card::new(value, CHASED[i % 4])
+                })
+                .collect(),
+            Stack::EightKings => (0..52).map(|i| Card::new(EIGHT_KINGS[i % 13], CHASED[i % 4])).collect(),
+        }
+    }
+
+    pub fn deck(self) -> Deck {
+        Deck::from(self.cards())
+    }
+
+    /// The card at a position from 1 to 52
+    pub fn card(self, position: usize) -> Option<Card> {
+        match position {
+            0 => None,
+            n => self.cards().get(n - 1).copied(),
+        }
+    }
+
+    /// Where a card is in the stack, from 1 to 52
+    pub fn position(self, card: &Card) -> usize {
+        self.cards().iter().position(|c| c == card).expect("every card is in a stack") + 1
+    }
+
+    /// The stack after it has been handled
+    pub fn after(self, routine: &Routine) -> Deck {
+        let mut deck = self.deck();
+        deck.permute(&routine.permutation(52)).expect("52 cards");
+        deck
+    }
+
+    /// Where a card is once the stack has been handled
+    pub fn find_after(self, card: &Card, routine: &Routine) -> usize {
+        routine.track(52, self.position(card))
+    }
+}
+
+impl From<Stack> for Deck {
+    fn from(stack: Stack) -> Self {
+        stack.deck()
+    }
+}
+
+/// Something done to the deck that leaves it in a known order
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Handling {
+    /// Cut this many cards from the top to the bottom
+    Cut(usize),
+    OutFaro,
+    InFaro,
+    /// Deal this many cards into a pile, one at a time, and drop the rest
+    /// of the deck on top of it
+    Deal(usize),
+    /// Deal the deck into this many piles and stack them, the first on top
+    Piles(usize),
+}
+
+impl Handling {
+    /// How this rearranges `n` cards
+    pub fn permutation(self, n: usize) -> Permutation {
+        let mut from: Vec<usize> = (0..n).collect();
+        match self {
+            Handling::Cut(count) => from.rotate_left(count.min(n)),
+            Handling::OutFaro => faro(&mut from, n.div_ceil(2), true),
+            Handling::InFaro => faro(&mut from, n / 2, false),
+            Handling::Deal(count) => {
+                let count = count.min(n);
+                from[..count].reverse();
+                from.rotate_left(count);
+            }
+            Handling::Piles(piles) => deal_piles(&mut from, piles),
+        }
+        Permutation::new(from).expect("a rearrangement")
+    }
+}
+
+/// A sequence of cuts, faros and deals done in order
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Routine {
+    pub steps: Vec<Handling>,
+}
+
+impl Routine {
+    pub fn new(steps: &[Handling]) -> Self {
+        Routine { steps: steps.to_vec() }
+    }
+
+    /// How the whole routine rearranges `n` cards
+    pub fn permutation(&self, n: usize) -> Permutation {
+        self.steps
+            .iter()
+            .fold(Permutation::identity(n), |p, step| p.then(&step.permutation(n)))
+    }
+
+    /// Where the card starting at `position` in `n` cards ends up
+    pub fn track(&self, n: usize, position: usize) -> usize {
+        let inverse = self.permutation(n).inverse();
+        inverse.source(position - 1) + 1
+    }
+
+    /// How many times to repeat the routine to get back to the start
+    pub fn order(&self, n: usize) -> BigUint {
+        self.permutation(n).order()
+    }
+}
+
+impl TryFrom<&str> for Stack {
+    type Error = io::Error;
+    fn try_from(name: &str) -> Result<Stack, Self::Error> {
+        match name.to_lowercase().replace(|c: char| !c.is_alphanumeric(), "").as_str() {
+            "mnemonica" | "tamariz" => Ok(Stack::Mnemonica),
+            "aronson" => Ok(Stack::Aronson),
+            "sistebbins" | "stebbins" => Ok(Stack::SiStebbins),
+            "eightkings" | "8kings" => Ok(Stack::EightKings),
+            _ => Err(io::Error::from(io::ErrorKind::InvalidInput)),
+        }
+    }
+}
+
+#[test]
+fn memorized_stacks() {
+    for stack in Stack::ALL.iter() {
+        let deck = stack.deck();
+        assert_eq!(deck.len(), 52);
+        assert!(Audit::default().check(Census::new().count("stack", &deck)).is_ok(), "{:?}", stack);
+    }
+
+    let ace = Card::new(Value::Ace, Suit::Spades);
+    assert_eq!(Stack::Mnemonica.position(&ace), 7);
+    assert_eq!(Stack::Mnemonica.card(52), Some(Card::new(Value::Nine, Suit::Diamonds)));
+    assert_eq!(Stack::Aronson.card(1), Some(Card::new(Value::Jack, Suit::Spades)));
+    assert_eq!(Stack::SiStebbins.card(2), Some(Card::new(Value::Four, Suit::Hearts)));
+    assert_eq!(Stack::EightKings.card(14), Some(Card::new(Value::Eight, Suit::Hearts)));
+    assert_eq!(Stack::try_from("Si Stebbins").ok(), Some(Stack::SiStebbins));
+
+    // Cut the ace to the top, then two out faros send it nowhere, and an
+    // in faro sends it second
+    let routine = Routine::new(&[Handling::Cut(6), Handling::OutFaro, Handling::OutFaro]);
+    assert_eq!(Stack::Mnemonica.find_after(&ace, &routine), 1);
+    assert_eq!(Routine::new(&[Handling::Cut(6), Handling::InFaro]).track(52, 7), 2);
+    assert_eq!(Routine::new(&[Handling::OutFaro]).order(52), BigUint::from(8u32));
+
+    // Dealing five cards reverses them onto the bottom
+    let dealt = Stack::Mnemonica.after(&Routine::new(&[Handling::Deal(5)]));
+    assert_eq!(dealt.cards[0], Stack::Mnemonica.cards()[5]);
+    assert_eq!(dealt.cards[47], Stack::Mnemonica.cards()[4]);
+    assert_eq!(dealt.cards[51], Stack::Mnemonica.cards()[0]);
+}
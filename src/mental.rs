@@ -0,0 +1,350 @@
+//! Dealing among players who don't trust a dealer, by SRA mental poker
+//!
+//! Every player locks the whole deck with their own commutative key and
+//! shuffles it. Then each in turn swaps their lock for a different key on
+//! every card. A card is shown to its owner when everyone else hands over
+//! their key for it, so nobody else ever learns it.
+
+use super::*;
+use num_bigint::BigUint;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
+use std::io::{Read, Write};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// The 1024-bit safe prime of the Oakley group 2 (RFC 2409)
+const OAKLEY_PRIME: &str = "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74\
+                            020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437\
+                            4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
+                            EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE65381FFFFFFFFFFFFFFFF";
+
+/// The most numbers a message can hold, one for each card
+const MAX_COUNT: u32 = 52;
+
+/// What the players send each other
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Message {
+    /// The encrypted deck, top first
+    Deck(Vec<BigUint>),
+    /// Keys for the receiver's cards, in the order they were dealt
+    Keys(Vec<BigUint>),
+}
+
+impl Message {
+    /// Write the message as a tag byte, a count, then each number with its
+    /// length, all big-endian
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let (tag, numbers) = match self {
+            Message::Deck(numbers) => (0u8, numbers),
+            Message::Keys(numbers) => (1u8, numbers),
+        };
+        w.write_all(&[tag])?;
+        w.write_all(&(numbers.len() as u32).to_be_bytes())?;
+        for n in numbers.iter() {
+            let bytes = n.to_bytes_be();
+            w.write_all(&(bytes.len() as u32).to_be_bytes())?;
+            w.write_all(&bytes)?;
+        }
+        w.flush()
+    }
+
+    /// Read a message, refusing more than 52 numbers or any number longer
+    /// than `max_bytes`, so a hostile peer can't make us allocate much
+    pub fn read_from<R: Read>(r: &mut R, max_bytes: usize) -> io::Result<Self> {
+        let mut tag = [0; 1];
+        r.read_exact(&mut tag)?;
+        let count = read_u32(r)?;
+        if count > MAX_COUNT {
+            return Err(io::Error::from(io::ErrorKind::InvalidData));
+        }
+        let mut numbers = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let len = read_u32(r)? as usize;
+            if len > max_bytes {
+                return Err(io::Error::from(io::ErrorKind::InvalidData));
+            }
+            let mut bytes = vec![0; len];
+            r.read_exact(&mut bytes)?;
+            numbers.push(BigUint::from_bytes_be(&bytes));
+        }
+        match tag[0] {
+            0 => Ok(Message::Deck(numbers)),
+            1 => Ok(Message::Keys(numbers)),
+            _ => Err(io::Error::from(io::ErrorKind::InvalidData)),
+        }
+    }
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    r.read_exact(&mut bytes)?;
+    Ok(u32::from_be_bytes(bytes))
+}
+
+/// How one seat talks to the others; messages between two seats must
+/// arrive in the order they were sent
+pub trait Transport {
+    fn send(&mut self, to: usize, message: Message) -> io::Result<()>;
+    fn receive(&mut self, from: usize) -> io::Result<Message>;
+}
+
+/// Channels between threads of one process
+pub struct LocalTransport {
+    senders: Vec<Sender<Message>>,
+    receivers: Vec<Receiver<Message>>,
+}
+
+impl LocalTransport {
+    /// A transport for each seat, all connected to each other
+    pub fn connected(seats: usize) -> Vec<LocalTransport> {
+        let mut senders: Vec<Vec<Sender<Message>>> = (0..seats).map(|_| Vec::new()).collect();
+        let mut receivers: Vec<Vec<Receiver<Message>>> = (0..seats).map(|_| Vec::new()).collect();
+        for from in senders.iter_mut() {
+            for to in receivers.iter_mut() {
+                let (tx, rx) = channel();
+                from.push(tx);
+                to.push(rx);
+            }
+        }
+        senders
+            .into_iter()
+            .zip(receivers)
+            .map(|(senders, receivers)| LocalTransport { senders, receivers })
+            .collect()
+    }
+}
+
+impl Transport for LocalTransport {
+    fn send(&mut self, to: usize, message: Message) -> io::Result<()> {
+        self.senders[to]
+            .send(message)
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+
+    fn receive(&mut self, from: usize) -> io::Result<Message> {
+        self.receivers[from]
+            .recv()
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+}
+
+/// One stream to each other seat, like a `TcpStream`
+pub struct StreamTransport<S: Read + Write> {
+    /// The stream to each seat, `None` for this one
+    pub streams: Vec<Option<S>>,
+    /// The longest number accepted, in bytes
+    pub max_bytes: usize,
+}
+
+impl<S: Read + Write> StreamTransport<S> {
+    /// Streams for a deal modulo the default prime
+    pub fn new(streams: Vec<Option<S>>) -> Self {
+        StreamTransport { streams, max_bytes: OAKLEY_PRIME.len() / 2 }
+    }
+
+    /// Accept numbers as long as this prime
+    pub fn prime(mut self, prime: &BigUint) -> Self {
+        self.max_bytes = prime.to_bytes_be().len();
+        self
+    }
+
+    fn stream(&mut self, seat: usize) -> io::Result<&mut S> {
+        self.streams
+            .get_mut(seat)
+            .and_then(Option::as_mut)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotConnected))
+    }
+}
+
+impl<S: Read + Write> Transport for StreamTransport<S> {
+    fn send(&mut self, to: usize, message: Message) -> io::Result<()> {
+        message.write_to(self.stream(to)?)
+    }
+
+    fn receive(&mut self, from: usize) -> io::Result<Message> {
+        let max_bytes = self.max_bytes;
+        Message::read_from(self.stream(from)?, max_bytes)
+    }
+}
+
+/// A Pohlig–Hellman key: encrypt with `e`, decrypt with `d`
+struct Key {
+    e: BigUint,
+    d: BigUint,
+}
+
+impl Key {
+    fn generate<R: Rng + ?Sized>(prime: &BigUint, rng: &mut R) -> Self {
+        let order = prime - 1u32;
+        let mut bytes = vec![0; order.to_bytes_be().len()];
+        loop {
+            rng.fill_bytes(&mut bytes);
+            let e = BigUint::from_bytes_be(&bytes) % &order;
+            if let Some(d) = e.modinv(&order) {
+                if e > BigUint::from(1u32) {
+                    return Key { e, d };
+                }
+            }
+        }
+    }
+}
+
+/// An SRA deal of a pattern among its seats
+#[derive(Debug, Clone)]
+pub struct MentalPoker {
+    pub pattern: DealPattern,
+    /// The prime everyone works modulo; it must be a safe prime
+    pub prime: BigUint,
+}
+
+impl MentalPoker {
+    pub fn new(pattern: DealPattern) -> Self {
+        let prime = BigUint::parse_bytes(OAKLEY_PRIME.as_bytes(), 16).expect("prime");
+        MentalPoker { pattern, prime }
+    }
+
+    pub fn prime(mut self, prime: BigUint) -> Self {
+        self.prime = prime;
+        self
+    }
+
+    pub fn seats(&self) -> usize {
+        self.pattern.hand_sizes.len()
+    }
+
+    /// Each card as a square modulo the prime, so the deck doesn't leak
+    /// which cards are quadratic residues
+    fn encoded(&self) -> Vec<(Card, BigUint)> {
+        Deck::default()
+            .cards
+            .into_iter()
+            .enumerate()
+            .map(|(i, card)| (card, BigUint::from(i as u32 + 2).modpow(&BigUint::from(2u32), &self.prime)))
+            .collect()
+    }
+
+    /// Play one seat's part of the deal, and get back its hand
+    ///
+    /// Every seat must call this at the same time. Fails with
+    /// `InvalidData` if another player sent something that isn't a card.
+    pub fn play<T: Transport, R: Rng + ?Sized>(&self, seat: usize, transport: &mut T, rng: &mut R) -> io::Result<Hand> {
+        let seats = self.seats();
+        if seats < 2 || seat >= seats {
+            return Err(io::Error::from(io::ErrorKind::InvalidInput));
+        }
+        let (prev, next) = ((seat + seats - 1) % seats, (seat + 1) % seats);
+        let last = seats - 1;
+        let encoded = self.encoded();
+
+        // Lock and shuffle the whole deck
+        let lock = Key::generate(&self.prime, rng);
+        let mut deck = match seat {
+            0 => encoded.iter().map(|(_, m)| m.clone()).collect(),
+            _ => self.receive_deck(transport, prev)?,
+        };
+        deck = deck.iter().map(|m| m.modpow(&lock.e, &self.prime)).collect();
+        deck.shuffle(rng);
+        transport.send(next, Message::Deck(deck))?;
+
+        // Swap the lock for a key on each card
+        let mut deck = self.receive_deck(transport, prev)?;
+        let keys: Vec<Key> = deck.iter().map(|_| Key::generate(&self.prime, rng)).collect();
+        for (m, key) in deck.iter_mut().zip(keys.iter()) {
+            *m = m.modpow(&lock.d, &self.prime).modpow(&key.e, &self.prime);
+        }
+        let deck = if seat == last {
+            for other in 0..last {
+                transport.send(other, Message::Deck(deck.clone()))?;
+            }
+            deck
+        } else {
+            transport.send(next, Message::Deck(deck))?;
+            self.receive_deck(transport, last)?
+        };
+
+        // Hand every other seat the keys to its own cards
+        let destinations = self.pattern
+            .destinations(deck.len())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let positions = |s: usize| -> Vec<usize> {
+            destinations
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| **d == Destination::Seat(s))
+                .map(|(i, _)| i)
+                .collect()
+        };
+        for other in (0..seats).filter(|s| *s != seat) {
+            let disclosed = positions(other).into_iter().map(|i| keys[i].d.clone()).collect();
+            transport.send(other, Message::Keys(disclosed))?;
+        }
+
+        let mine = positions(seat);
+        let mut cards: Vec<BigUint> = mine.iter().map(|i| deck[*i].modpow(&keys[*i].d, &self.prime)).collect();
+        for other in (0..seats).filter(|s| *s != seat) {
+            let disclosed = match transport.receive(other)? {
+                Message::Keys(keys) if keys.len() == cards.len() => keys,
+                _ => return Err(io::Error::from(io::ErrorKind::InvalidData)),
+            };
+            for (m, d) in cards.iter_mut().zip(disclosed.iter()) {
+                *m = m.modpow(d, &self.prime);
+            }
+        }
+
+        cards
+            .iter()
+            .map(|m| {
+                encoded
+                    .iter()
+                    .find(|(_, e)| e == m)
+                    .map(|(card, _)| *card)
+                    .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))
+            })
+            .collect::<io::Result<Vec<Card>>>()
+            .map(Hand::from)
+    }
+
+    /// A whole deck from another seat, every card less than the prime
+    fn receive_deck<T: Transport>(&self, transport: &mut T, from: usize) -> io::Result<Vec<BigUint>> {
+        match transport.receive(from)? {
+            Message::Deck(deck) if deck.len() == 52 && deck.iter().all(|m| *m < self.prime) => Ok(deck),
+            _ => Err(io::Error::from(io::ErrorKind::InvalidData)),
+        }
+    }
+
+    /// Deal every seat in this process, each on its own thread
+    pub fn deal_local(&self) -> io::Result<Vec<Hand>> {
+        let transports = LocalTransport::connected(self.seats());
+        std::thread::scope(|scope| {
+            let players: Vec<_> = transports
+                .into_iter()
+                .enumerate()
+                .map(|(seat, mut transport)| scope.spawn(move || self.play(seat, &mut transport, &mut thread_rng())))
+                .collect();
+            players
+                .into_iter()
+                .map(|p| p.join().unwrap_or_else(|_| Err(io::Error::from(io::ErrorKind::Other))))
+                .collect()
+        })
+    }
+}
+
+#[test]
+fn mental_poker() -> io::Result<()> {
+    let hands = MentalPoker::new(DealPattern::round_robin(3, 5)).deal_local()?;
+    assert_eq!(hands.iter().map(Hand::len).collect::<Vec<_>>(), vec![5, 5, 5]);
+    let dealt: std::collections::HashSet<Card> = hands.into_iter().flatten().collect();
+    assert_eq!(dealt.len(), 15);
+
+    let mut bytes = Vec::new();
+    let message = Message::Keys(vec![BigUint::from(0u32), BigUint::from(65_537u32)]);
+    message.write_to(&mut bytes)?;
+    assert_eq!(Message::read_from(&mut bytes.as_slice(), 128)?, message);
+    assert!(Message::read_from(&mut bytes.as_slice(), 2).is_err());
+
+    // A peer can't ask for more than a deck of numbers
+    let huge = [&[0u8][..], &u32::MAX.to_be_bytes()].concat();
+    assert!(Message::read_from(&mut huge.as_slice(), 128).is_err());
+
+    Ok(())
+}
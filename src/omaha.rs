@@ -0,0 +1,89 @@
+//! Omaha hands, which use exactly two hole cards and three from the board
+
+use super::*;
+
+/// The five-card hands an Omaha player may make, or none if there are too
+/// few or too many cards
+fn omaha_hands(hole: &[Card], board: &[Card]) -> Vec<Vec<Card>> {
+    if !(4..=6).contains(&hole.len()) || !(3..=5).contains(&board.len()) {
+        return Vec::new();
+    }
+    let boards = choose(board, 3);
+    choose(hole, 2)
+        .into_iter()
+        .flat_map(|two| boards.iter().map(move |three| [two.as_slice(), three].concat()))
+        .collect()
+}
+
+/// The best high hand from four to six hole cards and three to five on
+/// the board
+pub fn omaha_high(hole: &[Card], board: &[Card]) -> Option<Best<Strength>> {
+    best_of(&omaha_hands(hole, board), |five| Some(evaluate_unchecked(five)))
+}
+
+/// The best eight-or-better low, if the hole cards and board make one
+pub fn omaha_low(hole: &[Card], board: &[Card]) -> Option<Best<Low>> {
+    best_of(&omaha_hands(hole, board), qualified_low)
+}
+
+/// A player's hands in a high-low split game
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HiLo {
+    pub high: Best<Strength>,
+    /// `None` if the player has no qualifying low
+    pub low: Option<Best<Low>>,
+}
+
+impl HiLo {
+    /// Both halves of an Omaha hi/lo hand
+    pub fn omaha(hole: &[Card], board: &[Card]) -> Option<Self> {
+        Some(HiLo { high: omaha_high(hole, board)?, low: omaha_low(hole, board) })
+    }
+}
+
+/// Each player's share of a high-low pot: half to the best high and half
+/// to the best low, or all to the high when nobody qualifies for low
+pub fn hi_lo_shares(hands: &[HiLo]) -> Vec<f64> {
+    let halves = hi_lo_halves(hands);
+    (0..hands.len())
+        .map(|i| halves.iter().map(|half| half[i]).sum::<f64>() / halves.len() as f64)
+        .collect()
+}
+
+/// The shares of the high half and, if anyone has a low, the low half
+pub(crate) fn hi_lo_halves(hands: &[HiLo]) -> Vec<Vec<f64>> {
+    let high = pot_shares(&hands.iter().map(|h| Some(h.high.strength)).collect::<Vec<_>>());
+    let lows: Vec<Option<Low>> = hands.iter().map(|h| h.low.as_ref().map(|l| l.strength)).collect();
+    if lows.iter().all(Option::is_none) {
+        vec![high]
+    } else {
+        vec![high, pot_shares(&lows)]
+    }
+}
+
+#[test]
+fn omaha_hands_use_two_hole_cards() {
+    let cards = |s: &str| parse_cards(s).expect("cards");
+
+    // Four hearts on the board are no flush without two hearts in hand
+    let board = cards("2H 5H 9H KH 7C");
+    let high = omaha_high(&cards("AH AS QD JC"), &board).expect("hand");
+    assert_eq!(high.strength.category(), Category::Pair);
+    let flush = omaha_high(&cards("AH 3H QD JC"), &board).expect("hand");
+    assert_eq!(flush.strength.category(), Category::Flush);
+    assert_eq!(flush.cards, cards("AH 3H 5H 9H KH"));
+
+    // A-2 in hand with three low cards on board; a lone ace doesn't make a low
+    let board = cards("3C 4D 8S KH KD");
+    let nut = HiLo::omaha(&cards("AS 2S QC QH"), &board).expect("hand");
+    assert_eq!(nut.low.as_ref().expect("low").cards, cards("AS 2S 3C 4D 8S"));
+    let trips = HiLo::omaha(&cards("KS 9C AH JD"), &board).expect("hand");
+    assert!(trips.low.is_none());
+    // Ties on both halves quarter the pot
+    let split = HiLo::omaha(&cards("AC 2C KC 5H"), &board).expect("hand");
+
+    assert_eq!(hi_lo_shares(&[nut.clone(), trips.clone()]), vec![0.5, 0.5]);
+    assert_eq!(hi_lo_shares(&[nut, trips.clone(), split]), vec![0.25, 0.25, 0.5]);
+    assert_eq!(hi_lo_shares(&[trips]), vec![1.0]);
+    assert!(omaha_high(&cards("AS 2S"), &board).is_none());
+}
@@ -0,0 +1,226 @@
+//! Rearrangements of a deck, and how to combine, undo and number them
+
+use super::*;
+pub use num_bigint::BigUint;
+use std::convert::TryFrom;
+
+/// A rearrangement of n cards
+///
+/// The card at position `i` afterwards is the one that was at `source(i)`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Permutation {
+    from: Vec<usize>,
+}
+
+impl Permutation {
+    /// Leave every card where it is
+    pub fn identity(n: usize) -> Self {
+        Permutation { from: (0..n).collect() }
+    }
+
+    /// Each new position takes the card from the given old position
+    ///
+    /// Returns `None` unless every old position is used exactly once.
+    pub fn new(from: Vec<usize>) -> Option<Self> {
+        let mut seen = vec![false; from.len()];
+        for i in from.iter() {
+            match seen.get_mut(*i) {
+                Some(s) if !*s => *s = true,
+                _ => return None,
+            }
+        }
+        Some(Permutation { from })
+    }
+
+    /// The rearrangement that turns one order of cards into the other
+    ///
+    /// Returns `None` unless both hold the same cards.
+    pub fn between<'a, A: Cards<'a>, B: Cards<'a>>(before: &'a A, after: &'a B) -> Option<Self> {
+        let before: Vec<&Card> = before.cards().collect();
+        let mut used = vec![false; before.len()];
+        let mut from = Vec::with_capacity(before.len());
+
+        for card in after.cards() {
+            let i = (0..before.len()).find(|i| !used[*i] && before[*i] == card)?;
+            used[i] = true;
+            from.push(i);
+        }
+
+        if from.len() == before.len() {
+            Some(Permutation { from })
+        } else {
+            None
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.from.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.from.is_empty()
+    }
+
+    /// The old position of the card now at `position`
+    pub fn source(&self, position: usize) -> usize {
+        self.from[position]
+    }
+
+    /// Rearrange the items, or `None` if there are the wrong number of them
+    pub fn apply<T: Clone>(&self, items: &[T]) -> Option<Vec<T>> {
+        if items.len() == self.len() {
+            Some(self.from.iter().map(|i| items[*i].clone()).collect())
+        } else {
+            None
+        }
+    }
+
+    /// This rearrangement followed by the other one
+    pub fn then(&self, other: &Permutation) -> Permutation {
+        Permutation { from: other.from.iter().map(|i| self.from[*i]).collect() }
+    }
+
+    /// The rearrangement that puts the cards back
+    pub fn inverse(&self) -> Permutation {
+        let mut from = vec![0; self.len()];
+        for (to, i) in self.from.iter().enumerate() {
+            from[*i] = to;
+        }
+        Permutation { from }
+    }
+
+    /// The cycles of positions that the cards move around, leaving out the
+    /// cards that stay put
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.len()];
+        let mut cycles = Vec::new();
+
+        for start in 0..self.len() {
+            let mut cycle = Vec::new();
+            let mut i = start;
+            while !seen[i] {
+                seen[i] = true;
+                cycle.push(i);
+                i = self.from[i];
+            }
+            if cycle.len() > 1 {
+                cycles.push(cycle);
+            }
+        }
+
+        cycles
+    }
+
+    /// How many times the rearrangement must be repeated to get back to
+    /// the start, like 8 for an out faro of 52 cards
+    pub fn order(&self) -> BigUint {
+        self.cycles().iter().fold(BigUint::from(1u32), |order, cycle| lcm(order, cycle.len()))
+    }
+
+    /// The number of this rearrangement among all n! of them in
+    /// lexicographic order, from its Lehmer code
+    pub fn rank(&self) -> BigUint {
+        let n = self.len();
+        let mut rank = BigUint::from(0u32);
+        for i in 0..n {
+            let smaller = self.from[i + 1..].iter().filter(|j| **j < self.from[i]).count();
+            rank = rank * (n - i) + smaller;
+        }
+        rank
+    }
+
+    /// The rearrangement of n cards with this rank, or `None` if the rank
+    /// is n! or more
+    pub fn unrank(n: usize, rank: &BigUint) -> Option<Self> {
+        let mut rank = rank.clone();
+        let mut code = vec![0; n];
+        for i in (0..n).rev() {
+            let radix = n - i;
+            code[i] = usize::try_from(&rank % radix).ok()?;
+            rank /= radix;
+        }
+        if rank != BigUint::from(0u32) {
+            return None;
+        }
+
+        let mut left: Vec<usize> = (0..n).collect();
+        Some(Permutation { from: code.into_iter().map(|c| left.remove(c)).collect() })
+    }
+}
+
+fn lcm(a: BigUint, b: usize) -> BigUint {
+    // gcd(a, b) is gcd(b, a % b), which fits in a usize
+    let mut x = b;
+    let mut y = usize::try_from(&a % b).expect("remainder is below b");
+    while y != 0 {
+        let r = x % y;
+        x = y;
+        y = r;
+    }
+    a / x * b
+}
+
+impl Deck {
+    /// Rearrange the deck, if the permutation is for this many cards
+    pub fn permute(&mut self, permutation: &Permutation) -> DealResult<()> {
+        self.cards = permutation.apply(&self.cards).ok_or(DealError::OutOfBounds)?;
+        Ok(())
+    }
+}
+
+impl Hand {
+    /// Rearrange the hand, if the permutation is for this many cards
+    pub fn permute(&mut self, permutation: &Permutation) -> DealResult<()> {
+        self.cards = permutation.apply(&self.cards).ok_or(DealError::OutOfBounds)?;
+        Ok(())
+    }
+}
+
+#[test]
+fn faro_permutations() -> DealResult<()> {
+    use rand::SeedableRng;
+    let mut rng = rand_pcg::Pcg64Mcg::seed_from_u64(5);
+    let fresh = Deck::default();
+
+    let out = Permutation::between(&fresh, &fresh.clone().mixed(&[Mix::OutFaro])).expect("same cards");
+    let inn = Permutation::between(&fresh, &fresh.clone().mixed(&[Mix::InFaro])).expect("same cards");
+    assert_eq!(out.order(), BigUint::from(8u32));
+    assert_eq!(inn.order(), BigUint::from(52u32));
+    assert_eq!(out.then(&out.inverse()), Permutation::identity(52));
+
+    let mut shuffled = fresh.clone();
+    shuffled.mix_with(&[Mix::Riffle; 3], &mut rng);
+    let riffle = Permutation::between(&fresh, &shuffled).expect("same cards");
+    let mut deck = fresh.clone();
+    deck.permute(&riffle.then(&out))?;
+    assert_eq!(deck, shuffled.mixed(&[Mix::OutFaro]));
+
+    let rank = riffle.rank();
+    assert_eq!(Permutation::unrank(52, &rank), Some(riffle));
+    assert_eq!(Permutation::identity(52).rank(), BigUint::from(0u32));
+
+    let reverse = Permutation::new(vec![3, 2, 1, 0]).expect("permutation");
+    assert_eq!(reverse.rank(), BigUint::from(23u32));
+    assert_eq!(reverse.cycles(), vec![vec![0, 3], vec![1, 2]]);
+    assert_eq!(Permutation::unrank(4, &BigUint::from(24u32)), None);
+    assert!(Permutation::new(vec![0, 0]).is_none());
+
+    // One cycle for each prime up to 53 repeats more often than a u64 counts
+    let primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+    let mut from = Vec::new();
+    for p in primes.iter() {
+        let start = from.len();
+        from.extend((0..*p).map(|i| start + (i + 1) % p));
+    }
+    let long = Permutation::new(from).expect("permutation");
+    let product = primes.iter().fold(BigUint::from(1u32), |n, p| n * *p as u32);
+    assert!(product > BigUint::from(u64::MAX));
+    assert_eq!(long.order(), product);
+
+    let mut hand: Hand = "AS 2S 3S 4S".parse().expect("hand");
+    hand.permute(&reverse)?;
+    assert_eq!(hand, "4S 3S 2S AS".parse().expect("hand"));
+    assert!(hand.permute(&out).is_err());
+
+    Ok(())
+}
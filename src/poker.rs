@@ -0,0 +1,336 @@
+//! Ranking poker hands of five to seven cards
+//!
+//! Ranks are a card's `Value as u8`, from 0 for a two up to 12 for an ace,
+//! so a set of ranks fits in the low 13 bits of a `u16`.
+
+use super::*;
+use std::fmt;
+
+/// The kind of poker hand, from worst to best
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Category {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl Category {
+    pub const ALL: [Category; 9] = [
+        Category::HighCard,
+        Category::Pair,
+        Category::TwoPair,
+        Category::ThreeOfAKind,
+        Category::Straight,
+        Category::Flush,
+        Category::FullHouse,
+        Category::FourOfAKind,
+        Category::StraightFlush,
+    ];
+
+    /// How many ranks it takes to break a tie within the category
+    fn ranks(self) -> usize {
+        match self {
+            Category::HighCard | Category::Flush => 5,
+            Category::Pair => 4,
+            Category::TwoPair | Category::ThreeOfAKind => 3,
+            Category::FullHouse | Category::FourOfAKind => 2,
+            Category::Straight | Category::StraightFlush => 1,
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Category::HighCard      => "High card",
+                Category::Pair          => "Pair",
+                Category::TwoPair       => "Two pair",
+                Category::ThreeOfAKind  => "Three of a kind",
+                Category::Straight      => "Straight",
+                Category::Flush         => "Flush",
+                Category::FullHouse     => "Full house",
+                Category::FourOfAKind   => "Four of a kind",
+                Category::StraightFlush => "Straight flush",
+            }
+        )
+    }
+}
+
+/// How good a poker hand is; a stronger hand compares greater, and equal
+/// hands split the pot
+///
+/// The category sits above the ranks that break ties, most important
+/// first, four bits each.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Strength(u32);
+
+impl Strength {
+    pub(crate) fn new(category: Category, ranks: &[u8]) -> Self {
+        let packed = (0..5).fold(0, |acc, i| acc << 4 | u32::from(ranks.get(i).copied().unwrap_or(0)));
+        Strength((category as u32) << 20 | packed)
+    }
+
+    pub fn category(self) -> Category {
+        Category::ALL[(self.0 >> 20) as usize]
+    }
+
+    /// The values that break ties, most important first: the straight's
+    /// top card, the paired values, then the kickers
+    pub fn ranks(self) -> Vec<Value> {
+        (0..self.category().ranks())
+            .map(|i| VALUES[(self.0 >> (16 - 4 * i) & 0xF) as usize])
+            .collect()
+    }
+
+    /// The strength as a number, for tables and hashing
+    pub fn value(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for Strength {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.category())?;
+        for (i, value) in self.ranks().iter().enumerate() {
+            write!(f, "{}{}", if i == 0 { ", " } else { " " }, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// The best poker hand among five, six or seven cards, or `None` for any
+/// other number of cards
+pub fn evaluate(cards: &[Card]) -> Option<Strength> {
+    if (5..=7).contains(&cards.len()) {
+        Some(evaluate_unchecked(cards))
+    } else {
+        None
+    }
+}
+
+/// The best hand among five to seven different cards, without checking
+/// how many there are
+pub(crate) fn evaluate_unchecked(cards: &[Card]) -> Strength {
+    let mut suits = [0u16; 4];
+    let mut counts = [0u8; 13];
+    for card in cards.iter() {
+        suits[card.suit as usize] |= 1 << card.value as u16;
+        counts[card.value as usize] += 1;
+    }
+
+    // Seven cards with five of a suit can't also hold a full house or quads
+    if let Some(flush) = suits.iter().find(|s| s.count_ones() >= 5) {
+        return match straight_high(*flush) {
+            Some(high) => Strength::new(Category::StraightFlush, &[high]),
+            None => Strength::new(Category::Flush, &top_ranks(*flush, 5)),
+        };
+    }
+
+    let (mut quads, mut trips, mut pairs) = (0u16, 0u16, 0u16);
+    for (rank, count) in counts.iter().enumerate() {
+        match count {
+            4 => quads |= 1 << rank,
+            3 => trips |= 1 << rank,
+            2 => pairs |= 1 << rank,
+            _ => {}
+        }
+    }
+    let all = suits[0] | suits[1] | suits[2] | suits[3];
+
+    if quads != 0 {
+        let quad = highest(quads);
+        return Strength::new(Category::FourOfAKind, &[quad, highest(all & !(1 << quad))]);
+    }
+    if trips != 0 {
+        let trip = highest(trips);
+        let rest = (trips | pairs) & !(1 << trip);
+        if rest != 0 {
+            return Strength::new(Category::FullHouse, &[trip, highest(rest)]);
+        }
+    }
+    if let Some(high) = straight_high(all) {
+        return Strength::new(Category::Straight, &[high]);
+    }
+    if trips != 0 {
+        let trip = highest(trips);
+        let kickers = top_ranks(all & !(1 << trip), 2);
+        return Strength::new(Category::ThreeOfAKind, &[trip, kickers[0], kickers[1]]);
+    }
+    match pairs.count_ones() {
+        0 => Strength::new(Category::HighCard, &top_ranks(all, 5)),
+        1 => {
+            let pair = highest(pairs);
+            let kickers = top_ranks(all & !pairs, 3);
+            Strength::new(Category::Pair, &[pair, kickers[0], kickers[1], kickers[2]])
+        }
+        _ => {
+            let top = top_ranks(pairs, 2);
+            let kicker = highest(all & !(1 << top[0]) & !(1 << top[1]));
+            Strength::new(Category::TwoPair, &[top[0], top[1], kicker])
+        }
+    }
+}
+
+fn highest(ranks: u16) -> u8 {
+    15 - ranks.leading_zeros() as u8
+}
+
+/// The highest `n` ranks in the set, highest first
+fn top_ranks(mut ranks: u16, n: usize) -> [u8; 5] {
+    let mut top = [0; 5];
+    for slot in top.iter_mut().take(n) {
+        let rank = highest(ranks);
+        *slot = rank;
+        ranks &= !(1 << rank);
+    }
+    top
+}
+
+/// The top rank of the highest straight in the set, counting the ace as
+/// low too, so the wheel A-2-3-4-5 is five high
+fn straight_high(ranks: u16) -> Option<u8> {
+    // Shift up one so the low ace can sit at bit 0
+    let shifted = ranks << 1 | ranks >> 12 & 1;
+    (4..=13).rev().find(|top| shifted >> (top - 4) & 0x1F == 0x1F).map(|top| top as u8 - 1)
+}
+
+/// A hand's strength and the cards that make it
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Best<S> {
+    pub strength: S,
+    pub cards: Vec<Card>,
+}
+
+/// The best poker hand among five to seven cards, along with the five
+/// cards that make it
+pub fn best_five(cards: &[Card]) -> Option<Best<Strength>> {
+    if !(5..=7).contains(&cards.len()) {
+        return None;
+    }
+    best_of(&choose(cards, 5), |five| Some(evaluate_unchecked(five)))
+}
+
+/// Every way to choose `k` of the cards, keeping their order
+pub(crate) fn choose(cards: &[Card], k: usize) -> Vec<Vec<Card>> {
+    if k > cards.len() {
+        return Vec::new();
+    }
+    let mut picks: Vec<usize> = (0..k).collect();
+    let mut all = Vec::new();
+    loop {
+        all.push(picks.iter().map(|i| cards[*i]).collect());
+        match (0..k).rev().find(|i| picks[*i] < cards.len() - k + i) {
+            Some(i) => {
+                picks[i] += 1;
+                for j in i + 1..k {
+                    picks[j] = picks[j - 1] + 1;
+                }
+            }
+            None => return all,
+        }
+    }
+}
+
+/// The strongest of the candidate hands, skipping those that don't count
+pub(crate) fn best_of<S: Ord, F>(candidates: &[Vec<Card>], strength: F) -> Option<Best<S>>
+where
+    F: Fn(&[Card]) -> Option<S>,
+{
+    candidates
+        .iter()
+        .filter_map(|cards| strength(cards).map(|strength| Best { strength, cards: cards.clone() }))
+        .fold(None, |best: Option<Best<S>>, next| match best {
+            Some(best) if best.strength >= next.strength => Some(best),
+            _ => Some(next),
+        })
+}
+
+/// Each player's share of a pot, split evenly between the strongest hands;
+/// `None` is a hand that can't win, like a low that didn't qualify
+pub fn pot_shares<S: Ord>(strengths: &[Option<S>]) -> Vec<f64> {
+    let best = strengths.iter().flatten().max();
+    let winners = strengths.iter().filter(|s| s.is_some() && s.as_ref() == best).count();
+    strengths
+        .iter()
+        .map(|s| if s.is_some() && s.as_ref() == best { 1.0 / winners as f64 } else { 0.0 })
+        .collect()
+}
+
+impl Hand {
+    /// The best poker hand in five to seven cards
+    pub fn poker_strength(&self) -> Option<Strength> {
+        evaluate(&self.cards)
+    }
+}
+
+#[test]
+fn poker_hands() {
+    let strength = |s: &str| evaluate(&parse_cards(s).expect("cards")).expect("5 to 7 cards");
+    let ladder = [
+        "2C 3D 4H 5S 7C",
+        "AC KD QH JS 9C",
+        "2C 2D 3H 4S 5C 8D",
+        "2C 2D AH KS QC",
+        "3C 3D 2H 2S AC",
+        "3C 3D 2H 2S 4C 4D KH",
+        "2C 2D 2H AS KC",
+        "AC 2D 3H 4S 5C",
+        "2C 3D 4H 5S 6C",
+        "TC JD QH KS AC",
+        "2H 3H 4H 5H 7H",
+        "2H 2D 2C 3S 3H",
+        "2H 2D 2C 2S 3H",
+        "AH 2H 3H 4H 5H",
+        "TS JS QS KS AS",
+    ];
+    for pair in ladder.windows(2) {
+        assert!(strength(pair[0]) < strength(pair[1]), "{} < {}", pair[0], pair[1]);
+    }
+
+    // The best five of seven, and ties across different cards
+    let board = "KS QS 7D 7C 2H";
+    assert_eq!(strength(&format!("KD JS {}", board)).category(), Category::TwoPair);
+    assert_eq!(strength("JS TS KS QS 2S 7D 7C").category(), Category::Flush);
+    assert_eq!(strength("AS KD 7H 7C 2S"), strength("AD KH 7S 7D 2C"));
+    assert_eq!(strength("AS AD AC KS KD QD QH"), strength("AS AD AC KS KD 2C 3C"));
+    assert_eq!(strength("9S 8D 7C 6H 5S 4S 3S").ranks(), vec![Value::Nine]);
+    assert_eq!(strength("AH 2D 3C 4S 5H 6D").to_string(), "Straight, 6");
+    assert_eq!(strength("KH KD 9C 9S 4H 4D 2S").to_string(), "Two pair, K 9 4");
+    assert!(evaluate(&parse_cards("AH KH").expect("cards")).is_none());
+
+    let best = best_five(&parse_cards("2D 9H KS QS JS TS 7D").expect("cards")).expect("seven cards");
+    assert_eq!(best.strength.to_string(), "Straight, K");
+    assert_eq!(best.cards, parse_cards("9H KS QS JS TS").expect("cards"));
+    assert_eq!(pot_shares(&[Some(2), None, Some(2), Some(1)]), vec![0.5, 0.0, 0.5, 0.0]);
+
+    // Every five-card hand, counted by category
+    let deck = Deck::default().cards;
+    let mut counts = [0usize; 9];
+    let mut hand = [Card::default(); 5];
+    for a in 0..52 {
+        hand[0] = deck[a];
+        for b in a + 1..52 {
+            hand[1] = deck[b];
+            for c in b + 1..52 {
+                hand[2] = deck[c];
+                for d in c + 1..52 {
+                    hand[3] = deck[d];
+                    for card in deck[d + 1..].iter() {
+                        hand[4] = *card;
+                        counts[evaluate_unchecked(&hand).category() as usize] += 1;
+                    }
+                }
+            }
+        }
+    }
+    assert_eq!(counts, [1_302_540, 1_098_240, 123_552, 54_912, 10_200, 5_108, 3_744, 624, 40]);
+}
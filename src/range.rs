@@ -0,0 +1,345 @@
+//! Poker ranges: sets of two-card starting hands written like
+//! "QQ+, AKs, A5s-A2s, KQo, 22-55"
+
+use super::*;
+use rand::Rng;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// One of the 169 kinds of starting hand, ignoring which suits they are
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PreflopClass {
+    pub high: Value,
+    pub low: Value,
+    /// Always false for pairs
+    pub suited: bool,
+}
+
+impl PreflopClass {
+    /// Every class, pairs and unpaired hands from the top down
+    pub fn all() -> Vec<PreflopClass> {
+        let mut classes = Vec::with_capacity(169);
+        for high in VALUES.iter().rev() {
+            for low in VALUES.iter().rev().filter(|low| *low <= high) {
+                classes.push(PreflopClass { high: *high, low: *low, suited: false });
+                if low != high {
+                    classes.push(PreflopClass { high: *high, low: *low, suited: true });
+                }
+            }
+        }
+        classes
+    }
+
+    /// The class of two cards
+    pub fn of(first: Card, second: Card) -> Self {
+        let (high, low) = if first.value >= second.value { (first, second) } else { (second, first) };
+        PreflopClass { high: high.value, low: low.value, suited: high.value != low.value && high.suit == low.suit }
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    /// Every pair of cards in the class: six for a pair, four suited or
+    /// twelve offsuit
+    pub fn combos(&self) -> Vec<Hand> {
+        let mut combos = Vec::new();
+        for (i, high) in SUITS.iter().enumerate().rev() {
+            for (j, low) in SUITS.iter().enumerate().rev() {
+                let counts = if self.is_pair() { j < i } else { (high == low) == self.suited };
+                if counts {
+                    combos.push(Hand::from(vec![Card::new(self.high, *high), Card::new(self.low, *low)]));
+                }
+            }
+        }
+        combos
+    }
+}
+
+fn value_char(value: Value) -> char {
+    match value {
+        Value::Ten => 'T',
+        _ => value.to_string().chars().next().unwrap_or('?'),
+    }
+}
+
+impl fmt::Display for PreflopClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", value_char(self.high), value_char(self.low))?;
+        match (self.is_pair(), self.suited) {
+            (true, _) => Ok(()),
+            (false, true) => write!(f, "s"),
+            (false, false) => write!(f, "o"),
+        }
+    }
+}
+
+/// Hands like "AK", which may be suited or not
+#[derive(Copy, Clone)]
+struct Shape {
+    high: Value,
+    low: Value,
+    suited: Option<bool>,
+}
+
+impl Shape {
+    fn parse(s: &str) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidInput, format!("Not a hand: {}", s));
+        let chars: Vec<char> = s.chars().collect();
+        let value = |c: &char| Value::try_from(c.to_string().as_str()).map_err(|_| invalid());
+        let (first, second, suited) = match chars.as_slice() {
+            [a, b] => (value(a)?, value(b)?, None),
+            [a, b, 's'] | [a, b, 'S'] => (value(a)?, value(b)?, Some(true)),
+            [a, b, 'o'] | [a, b, 'O'] => (value(a)?, value(b)?, Some(false)),
+            _ => return Err(invalid()),
+        };
+        let (high, low) = if first >= second { (first, second) } else { (second, first) };
+        if high == low && suited.is_some() {
+            return Err(invalid());
+        }
+        Ok(Shape { high, low, suited })
+    }
+
+    fn with_values(self, high: Value, low: Value) -> Self {
+        Shape { high, low, ..self }
+    }
+
+    fn combos(self) -> Vec<Hand> {
+        let class = |suited| PreflopClass { high: self.high, low: self.low, suited };
+        match self.suited {
+            Some(suited) => class(suited).combos(),
+            None if self.high == self.low => class(false).combos(),
+            None => [class(true).combos(), class(false).combos()].concat(),
+        }
+    }
+}
+
+/// A weighted set of two-card hands, the higher card first
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Range {
+    combos: BTreeMap<(Key, Key), f64>,
+}
+
+/// A card ordered by suit as well as value, which `Card`'s `Ord` ignores
+type Key = (Value, Suit);
+
+fn combo((a, b): &(Key, Key)) -> Hand {
+    Hand::from(vec![Card::new(a.0, a.1), Card::new(b.0, b.1)])
+}
+
+impl Range {
+    pub fn new() -> Self {
+        Range::default()
+    }
+
+    /// Every two cards
+    pub fn random() -> Self {
+        let mut range = Range::new();
+        for class in PreflopClass::all() {
+            range.add(&class.combos(), 1.0);
+        }
+        range
+    }
+
+    /// Add the hands with a weight from 0 to 1, replacing any earlier
+    /// weight for them
+    pub fn add(&mut self, hands: &[Hand], weight: f64) {
+        for hand in hands.iter() {
+            if let [a, b] = hand.cards.as_slice() {
+                let (a, b) = ((a.value, a.suit), (b.value, b.suit));
+                self.combos.insert((a.max(b), a.min(b)), weight);
+            }
+        }
+    }
+
+    /// How many combos are in the range
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// The weight of a two-card hand, or zero if it isn't in the range
+    pub fn weight_of(&self, hand: &Hand) -> f64 {
+        match hand.cards.as_slice() {
+            [a, b] => {
+                let (a, b) = ((a.value, a.suit), (b.value, b.suit));
+                self.combos.get(&(a.max(b), a.min(b))).copied().unwrap_or(0.0)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// The combos counted by their weights
+    pub fn weight(&self) -> f64 {
+        self.combos.values().sum()
+    }
+
+    /// Each hand in the range with its weight
+    pub fn hands(&self) -> Vec<(Hand, f64)> {
+        self.combos.iter().map(|(k, w)| (combo(k), *w)).collect()
+    }
+
+    /// The range without the hands that hold a known card
+    pub fn without(&self, known: &[Card]) -> Range {
+        let combos = self.combos
+            .iter()
+            .filter(|(k, _)| !combo(k).cards().any(|c| known.contains(c)))
+            .map(|(k, w)| (*k, *w))
+            .collect();
+        Range { combos }
+    }
+
+    /// How much of each preflop class is in the range, as the weight of
+    /// its combos
+    pub fn classes(&self) -> BTreeMap<PreflopClass, f64> {
+        let mut classes = BTreeMap::new();
+        for ((a, b), weight) in self.combos.iter() {
+            *classes.entry(PreflopClass::of(Card::new(a.0, a.1), Card::new(b.0, b.1))).or_insert(0.0) += weight;
+        }
+        classes
+    }
+
+    /// Pick a hand in proportion to the weights
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Hand> {
+        let total = self.weight();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut pick = rng.gen::<f64>() * total;
+        for (k, weight) in self.combos.iter() {
+            if pick < *weight {
+                return Some(combo(k));
+            }
+            pick -= weight;
+        }
+        self.combos.keys().next_back().map(combo)
+    }
+}
+
+/// Parse ranges like "QQ+, AKs, A5s-A2s, KQo, 22-55, AhKh, random"
+///
+/// A weight follows a colon, like "AKo:0.5" or "50% random" for half of
+/// every hand.
+impl FromStr for Range {
+    type Err = io::Error;
+    fn from_str(s: &str) -> Result<Range, Self::Err> {
+        let mut range = Range::new();
+        for item in s.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let (item, weight) = split_weight(item)?;
+            range.add(&parse_item(item)?, weight);
+        }
+        Ok(range)
+    }
+}
+
+fn split_weight(item: &str) -> io::Result<(&str, f64)> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidInput, format!("Not a weight: {}", item));
+    let percent = |s: &str| -> io::Result<f64> {
+        match s.trim().strip_suffix('%') {
+            Some(p) => p.trim().parse::<f64>().map(|p| p / 100.0).map_err(|_| invalid()),
+            None => s.trim().parse::<f64>().map_err(|_| invalid()),
+        }
+    };
+
+    let (item, weight) = if let Some((item, weight)) = item.split_once(':') {
+        (item.trim(), percent(weight)?)
+    } else if let Some((weight, item)) = item.split_once(char::is_whitespace) {
+        (item.trim(), percent(weight)?)
+    } else {
+        (item, 1.0)
+    };
+
+    if (0.0..=1.0).contains(&weight) {
+        Ok((item, weight))
+    } else {
+        Err(invalid())
+    }
+}
+
+fn parse_item(item: &str) -> io::Result<Vec<Hand>> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidInput, format!("Not a range: {}", item));
+
+    if item.eq_ignore_ascii_case("random") || item.eq_ignore_ascii_case("any") {
+        return Ok(Range::random().hands().into_iter().map(|(hand, _)| hand).collect());
+    }
+
+    // A single combo, like "AhKh"
+    if item.len() == 4 && item.is_ascii() {
+        if let Ok(cards) = parse_cards(&format!("{} {}", &item[..2], &item[2..])) {
+            return if cards[0] == cards[1] { Err(invalid()) } else { Ok(vec![Hand::from(cards)]) };
+        }
+    }
+
+    let values = |from: Value, to: Value| VALUES.iter().copied().filter(move |v| *v >= from.min(to) && *v <= from.max(to));
+
+    if let Some((from, to)) = item.split_once('-') {
+        let (from, to) = (Shape::parse(from.trim())?, Shape::parse(to.trim())?);
+        return if from.high == from.low && to.high == to.low {
+            Ok(values(from.high, to.high).flat_map(|v| from.with_values(v, v).combos()).collect())
+        } else if from.high == to.high && from.suited == to.suited && from.high != from.low && to.high != to.low {
+            Ok(values(from.low, to.low).flat_map(|v| from.with_values(from.high, v).combos()).collect())
+        } else {
+            Err(invalid())
+        };
+    }
+
+    if let Some(shape) = item.strip_suffix('+') {
+        let shape = Shape::parse(shape)?;
+        return if shape.high == shape.low {
+            Ok(values(shape.high, Value::Ace).flat_map(|v| shape.with_values(v, v).combos()).collect())
+        } else {
+            Ok(values(shape.low, shape.high)
+                .filter(|v| *v < shape.high)
+                .flat_map(|v| shape.with_values(shape.high, v).combos())
+                .collect())
+        };
+    }
+
+    Ok(Shape::parse(item)?.combos())
+}
+
+#[test]
+fn ranges() -> io::Result<()> {
+    let cards = |s: &str| parse_cards(s).expect("cards");
+
+    assert_eq!(PreflopClass::all().len(), 169);
+    assert_eq!(PreflopClass::all().iter().map(|c| c.combos().len()).sum::<usize>(), 1326);
+    assert_eq!(Range::random().len(), 1326);
+    let class = PreflopClass::of(Card::new(Value::Five, Suit::Hearts), Card::new(Value::Ace, Suit::Hearts));
+    assert_eq!(class.to_string(), "A5s");
+
+    let range: Range = "QQ+, AKs, A5s-A2s, KQo, 22-55".parse()?;
+    assert_eq!(range.len(), 3 * 6 + 4 + 4 * 4 + 12 + 4 * 6);
+    assert_eq!(range.classes().len(), 3 + 1 + 4 + 1 + 4);
+    assert_eq!("ATs+".parse::<Range>()?.len(), 4 * 4);
+    assert_eq!("AK".parse::<Range>()?.len(), 16);
+    assert_eq!("AhKh, KhAh".parse::<Range>()?.len(), 1);
+
+    // Known cards block combos
+    let aces: Range = "AA".parse()?;
+    assert_eq!(aces.without(&cards("AS")).len(), 3);
+    assert_eq!(aces.without(&cards("AS AH")).len(), 1);
+
+    // Weights
+    let weighted: Range = "AKo:0.5, 50% random".parse()?;
+    assert_eq!(weighted.len(), 1326);
+    assert!((weighted.weight() - 663.0).abs() < 1e-9);
+    let mixed: Range = "KK, AKo:25%".parse()?;
+    assert!((mixed.weight() - 9.0).abs() < 1e-9);
+    assert_eq!(mixed.weight_of(&"KH AS".parse().expect("hand")), 0.25);
+    assert_eq!(mixed.weight_of(&"KH AH".parse().expect("hand")), 0.0);
+
+    use rand::SeedableRng;
+    let mut rng = rand_pcg::Pcg64Mcg::seed_from_u64(2);
+    let hand = mixed.sample(&mut rng).expect("a hand");
+    assert_eq!(hand.len(), 2);
+
+    for bad in ["AKx", "QQs", "A5s-K2s", "AKs:2", "AhAh", "XYZ"].iter() {
+        assert!(bad.parse::<Range>().is_err(), "{}", bad);
+    }
+
+    Ok(())
+}
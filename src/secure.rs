@@ -0,0 +1,165 @@
+//! Shuffling from the operating system's entropy, with a commitment to the
+//! deck order that is published before the deal and opened after the game
+//!
+//! The commitment is the SHA-256 of a random 32 byte salt followed by two
+//! bytes per card, top first: the value from 0 (Two) to 12 (Ace) and the
+//! suit from 0 to 3 in `SUITS` order.
+
+use super::*;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// A deck shuffled from OS entropy, along with the salt for its commitment
+pub struct SecureShuffle {
+    deck: Deck,
+    salt: [u8; 32],
+}
+
+impl SecureShuffle {
+    /// Shuffle the deck with a Fisher–Yates shuffle driven by `OsRng`
+    pub fn new(mut deck: Deck) -> Self {
+        let mut salt = [0; 32];
+        OsRng.fill_bytes(&mut salt);
+        secure_shuffle(&mut deck.cards, &mut OsRng);
+        SecureShuffle { deck, salt }
+    }
+
+    /// The hash to publish before any card is dealt
+    pub fn commitment(&self) -> Commitment {
+        Commitment::of(&self.salt, &self.deck.cards)
+    }
+
+    /// A copy of the shuffled deck to deal from
+    pub fn deck(&self) -> Deck {
+        self.deck.clone()
+    }
+
+    /// Everything the players need to check the commitment, for after the game
+    pub fn reveal(self) -> Reveal {
+        Reveal { cards: self.deck.cards, salt: self.salt }
+    }
+}
+
+/// Shuffle the cards so every order is equally likely, as long as the random
+/// source is
+pub fn secure_shuffle<T, R: RngCore + ?Sized>(cards: &mut [T], rng: &mut R) {
+    for i in (1..cards.len()).rev() {
+        cards.swap(i, below(i + 1, rng));
+    }
+}
+
+/// A number below `n` with no value favoured: draws from the incomplete
+/// block at the top of the range are thrown away rather than wrapped
+fn below<R: RngCore + ?Sized>(n: usize, rng: &mut R) -> usize {
+    let n = n as u64;
+    let zone = u64::MAX - u64::MAX % n;
+    loop {
+        let x = rng.next_u64();
+        if x < zone {
+            return (x % n) as usize;
+        }
+    }
+}
+
+/// A SHA-256 hash binding the dealer to a deck order
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Commitment(pub [u8; 32]);
+
+impl Commitment {
+    pub fn of(salt: &[u8; 32], cards: &[Card]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(salt);
+        for card in cards.iter() {
+            hasher.update([card.value as u8, card.suit as u8]);
+        }
+        Commitment(hasher.finalize().into())
+    }
+}
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.0.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Commitment {
+    type Err = io::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.len() != 64 || !s.is_ascii() {
+            return Err(io::Error::from(io::ErrorKind::InvalidInput));
+        }
+        let mut hash = [0; 32];
+        for (i, byte) in hash.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16)
+                .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+        }
+        Ok(Commitment(hash))
+    }
+}
+
+/// The deck order and salt behind a commitment, opened once the game is over
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Reveal {
+    /// The deck as it was shuffled, top first
+    pub cards: Vec<Card>,
+    pub salt: [u8; 32],
+}
+
+impl Reveal {
+    /// Whether this is the deck that was committed to
+    pub fn verify(&self, commitment: &Commitment) -> bool {
+        Commitment::of(&self.salt, &self.cards) == *commitment
+    }
+
+    /// Whether the cards seen during the game came off the top in this order
+    pub fn dealt(&self, seen: &[Card]) -> bool {
+        self.cards.starts_with(seen)
+    }
+
+    pub fn deck(&self) -> Deck {
+        Deck::from(self.cards.clone())
+    }
+}
+
+#[test]
+fn committed_shuffle() -> DealResult<()> {
+    use std::collections::HashMap;
+
+    let shuffle = SecureShuffle::new(Deck::default());
+    let commitment = shuffle.commitment();
+    let mut deck = shuffle.deck();
+    assert!(Audit::default().check(Census::new().count("deck", &deck)).is_ok());
+    assert_ne!(deck, Deck::default());
+
+    let mut hand = Hand::new();
+    for _ in 0..5 {
+        deck.deal(0, &mut hand)?;
+    }
+    let published = commitment.to_string();
+    let reveal = shuffle.reveal();
+    assert!(reveal.verify(&published.parse().expect("hex")));
+    assert!(reveal.dealt(&hand.cards));
+
+    let mut tampered = reveal.clone();
+    tampered.cards.swap(10, 20);
+    assert!(!tampered.verify(&commitment));
+    assert!("not a hash".parse::<Commitment>().is_err());
+
+    // Every order of three cards turns up about as often
+    let mut counts = HashMap::new();
+    for _ in 0..6000 {
+        let mut cards = [0, 1, 2];
+        secure_shuffle(&mut cards, &mut OsRng);
+        *counts.entry(cards).or_insert(0) += 1;
+    }
+    assert_eq!(counts.len(), 6);
+    assert!(counts.values().all(|n| (800..1200).contains(n)));
+
+    Ok(())
+}
@@ -0,0 +1,233 @@
+//! Serialize and Deserialize for the core types, behind the `serde` feature
+//!
+//! Cards are written as compact strings like "AS" or "TD". Decks and games
+//! are checked for duplicate cards when they are read back in.
+
+use super::*;
+use serde::de::{self, Deserializer, Visitor};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The compact form of a card: one character for the value, one for the suit
+pub fn card_code(card: &Card) -> String {
+    format!("{}{}", value_code(card.value), suit_code(card.suit))
+}
+
+fn value_code(value: Value) -> &'static str {
+    match value {
+        Value::Two => "2",
+        Value::Three => "3",
+        Value::Four => "4",
+        Value::Five => "5",
+        Value::Six => "6",
+        Value::Seven => "7",
+        Value::Eight => "8",
+        Value::Nine => "9",
+        Value::Ten => "T",
+        Value::Jack => "J",
+        Value::Queen => "Q",
+        Value::King => "K",
+        Value::Ace => "A",
+    }
+}
+
+fn suit_code(suit: Suit) -> &'static str {
+    match suit {
+        Suit::Diamonds => "D",
+        Suit::Clubs => "C",
+        Suit::Hearts => "H",
+        Suit::Spades => "S",
+    }
+}
+
+/// Reads any type that parses from a string
+struct StrVisitor<T>(std::marker::PhantomData<T>, &'static str);
+
+impl<'de, T> Visitor<'de> for StrVisitor<T>
+where T: for<'a> TryFrom<&'a str> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.1)
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<T, E> {
+        T::try_from(s).map_err(|_| E::invalid_value(de::Unexpected::Str(s), &self))
+    }
+}
+
+fn deserialize_str<'de, D, T>(d: D, expecting: &'static str) -> Result<T, D::Error>
+where D: Deserializer<'de>, T: for<'a> TryFrom<&'a str> {
+    d.deserialize_str(StrVisitor(std::marker::PhantomData, expecting))
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(value_code(*self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        deserialize_str(d, "a card value like \"Q\" or \"10\"")
+    }
+}
+
+impl Serialize for Suit {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(suit_code(*self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Suit {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        deserialize_str(d, "a suit like \"S\" or \"♠\"")
+    }
+}
+
+/// Cards parse through `FromStr`, this lets them share `StrVisitor`
+struct CardCode(Card);
+
+impl TryFrom<&str> for CardCode {
+    type Error = io::Error;
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse().map(CardCode)
+    }
+}
+
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&card_code(self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        deserialize_str(d, "a card like \"AS\" or \"10♥\"").map(|CardCode(card)| card)
+    }
+}
+
+/// Write a card as `{"value": "A", "suit": "S"}` instead of "AS"
+///
+/// Use it on a field with `#[serde(with = "cards::card_fields")]`.
+pub mod card_fields {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Fields {
+        value: Value,
+        suit: Suit,
+    }
+
+    pub fn serialize<S: Serializer>(card: &Card, s: S) -> Result<S::Ok, S::Error> {
+        Fields { value: card.value, suit: card.suit }.serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Card, D::Error> {
+        let Fields { value, suit } = Fields::deserialize(d)?;
+        Ok(Card::new(value, suit))
+    }
+}
+
+impl Serialize for Deck {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(self.cards())
+    }
+}
+
+/// A deck may only repeat a card if it holds more than one deck's worth,
+/// and then no more often than the number of decks it could hold
+impl<'de> Deserialize<'de> for Deck {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let cards = Vec::<Card>::deserialize(d)?;
+        let decks = cards.len().div_ceil(52);
+
+        let mut counts = HashMap::new();
+        for card in cards.iter() {
+            let count = counts.entry(*card).or_insert(0);
+            *count += 1;
+            if *count > decks {
+                return Err(de::Error::custom(format!("duplicate card {}", card_code(card))));
+            }
+        }
+
+        Ok(Deck { cards })
+    }
+}
+
+impl Serialize for Hand {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(self.cards())
+    }
+}
+
+impl<'de> Deserialize<'de> for Hand {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        Ok(Hand { cards: Vec::deserialize(d)? })
+    }
+}
+
+#[derive(Deserialize)]
+struct GameFields {
+    deck: Deck,
+    hands: Vec<Hand>,
+    #[serde(default)]
+    kitty: Hand,
+}
+
+/// No card may be in more places than there are decks in play, counting
+/// every card in the game like a `Deck` does
+impl<'de> Deserialize<'de> for Game {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let GameFields { deck, hands, kitty } = GameFields::deserialize(d)?;
+
+        let mut census = Census::new();
+        census.count("deck", &deck).count("kitty", &kitty);
+        for (i, hand) in hands.iter().enumerate() {
+            census.count(&format!("hand {}", i), hand);
+        }
+        let total = deck.len() + kitty.len() + hands.iter().map(Hand::len).sum::<usize>();
+        let decks: Vec<Card> = (0..total.div_ceil(52).max(1)).flat_map(|_| Deck::default().cards).collect();
+        if let Err(e) = Audit::new(&Deck::from(decks)).check(&census) {
+            if let Some(extra) = e.violations.iter().find(|v| matches!(v, Violation::Extra { .. })) {
+                return Err(de::Error::custom(extra));
+            }
+        }
+
+        Ok(Game { deck, hands, kitty })
+    }
+}
+
+#[test]
+fn serde_round_trip() -> Result<(), serde_json::Error> {
+    let card = Card::new(Value::Ten, Suit::Diamonds);
+    assert_eq!(serde_json::to_string(&card)?, "\"TD\"");
+    assert_eq!(serde_json::from_str::<Card>("\"10♦\"")?, card);
+
+    let game = Game::new(2, 5).expect("deal");
+    let json = serde_json::to_string(&game)?;
+    let back: Game = serde_json::from_str(&json)?;
+    assert_eq!(back.deck, game.deck);
+    assert_eq!(back.hands, game.hands);
+
+    #[derive(Serialize, Deserialize)]
+    struct Verbose(#[serde(with = "card_fields")] Card);
+    let json = serde_json::to_string(&Verbose(card))?;
+    assert_eq!(json, r#"{"value":"T","suit":"D"}"#);
+    assert_eq!(serde_json::from_str::<Verbose>(&json)?.0, card);
+
+    assert!(serde_json::from_str::<Deck>("[\"AS\", \"KD\", \"AS\"]").is_err());
+    // A two deck shoe holds two of each card, but not three
+    let mut shoe: Vec<String> = Deck::default().cards().chain(Deck::default().cards()).map(card_code).collect();
+    assert_eq!(serde_json::from_str::<Deck>(&serde_json::to_string(&shoe)?)?.len(), 104);
+    shoe[1] = "AS".into();
+    assert!(serde_json::from_str::<Deck>(&serde_json::to_string(&shoe)?).is_err());
+    let twice = r#"{"deck": ["AS", "KD"], "hands": [["QH"], ["AS"]]}"#;
+    assert!(serde_json::from_str::<Game>(twice).is_err());
+    let fine = r#"{"deck": ["AS", "KD"], "hands": [["QH"], ["JC"]]}"#;
+    assert_eq!(serde_json::from_str::<Game>(fine)?.hands[1], "JC".parse().expect("hand"));
+    assert!(serde_json::from_str::<Card>("\"ZZ\"").is_err());
+
+    Ok(())
+}
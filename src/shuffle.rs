@@ -1,5 +1,5 @@
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
 
 use super::*;
 
@@ -23,3 +23,174 @@ impl Shuffle for Hand {
         self.cards.shuffle(&mut thread_rng());
     }
 }
+
+/// Cards that can be mixed the ways people shuffle by hand
+pub trait Mixable where Self: Sized {
+    /// Mix the cards with each shuffle in turn, using this random source
+    fn mix_with<R: Rng + ?Sized>(&mut self, mixes: &[Mix], rng: &mut R);
+
+    /// Mix the cards with each shuffle in turn, like `&[Mix::Riffle; 7]`
+    fn mix(&mut self, mixes: &[Mix]) {
+        self.mix_with(mixes, &mut thread_rng());
+    }
+
+    fn mixed(mut self, mixes: &[Mix]) -> Self {
+        self.mix(mixes);
+        self
+    }
+}
+
+impl Mixable for Deck {
+    fn mix_with<R: Rng + ?Sized>(&mut self, mixes: &[Mix], rng: &mut R) {
+        for mix in mixes.iter() {
+            mix.apply(&mut self.cards, rng);
+        }
+    }
+}
+
+impl Mixable for Hand {
+    fn mix_with<R: Rng + ?Sized>(&mut self, mixes: &[Mix], rng: &mut R) {
+        for mix in mixes.iter() {
+            mix.apply(&mut self.cards, rng);
+        }
+    }
+}
+
+/// A way of mixing cards by hand, top card first
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Mix {
+    /// Every order equally likely, which no pair of hands can manage
+    Uniform,
+    /// Gilbert–Shannon–Reeds riffle: cut near the middle, then drop cards
+    /// from each half with odds in proportion to the cards left in it
+    Riffle,
+    /// Run small packets from the top into the other hand, this many cards
+    /// on average, so the packets come out in reverse order
+    Overhand { packet: usize },
+    /// Strip the deck into this many packets and stack them in reverse
+    Strip { packets: usize },
+    /// Cut near the middle and complete the cut
+    Cut,
+    /// Deal one card at a time into piles, then pick them up in order
+    Pile { piles: usize },
+    /// Perfect faro keeping the top card on top
+    OutFaro,
+    /// Perfect faro moving the top card to second
+    InFaro,
+}
+
+impl Mix {
+    pub fn apply<R: Rng + ?Sized>(self, cards: &mut Vec<Card>, rng: &mut R) {
+        let n = cards.len();
+        match self {
+            Mix::Uniform => cards.shuffle(rng),
+            Mix::Riffle => {
+                let cut = binomial_cut(n, rng);
+                let mut bottom = cards.split_off(cut);
+                let mut top = std::mem::take(cards);
+                top.reverse();
+                bottom.reverse();
+
+                while !top.is_empty() || !bottom.is_empty() {
+                    let from_top = rng.gen_range(0, top.len() + bottom.len()) < top.len();
+                    let half = if from_top { &mut top } else { &mut bottom };
+                    cards.extend(half.pop());
+                }
+            }
+            Mix::Overhand { packet } => {
+                let packet = packet.max(1);
+                let cuts: Vec<usize> = (1..n).filter(|_| rng.gen_range(0, packet) == 0).collect();
+                reverse_packets(cards, &cuts);
+            }
+            Mix::Strip { packets } => {
+                let count = packets.saturating_sub(1).min(n.saturating_sub(1));
+                let mut cuts: Vec<usize> = rand::seq::index::sample(rng, n.saturating_sub(1), count)
+                    .into_iter()
+                    .map(|i| i + 1)
+                    .collect();
+                cuts.sort_unstable();
+                reverse_packets(cards, &cuts);
+            }
+            Mix::Cut => {
+                let cut = binomial_cut(n, rng);
+                cards.rotate_left(cut);
+            }
+            Mix::Pile { piles } => deal_piles(cards, piles),
+            Mix::OutFaro => faro(cards, n.div_ceil(2), true),
+            Mix::InFaro => faro(cards, n / 2, false),
+        }
+    }
+}
+
+/// A cut point that is Binomial(n, 1/2), as a person cutting near the
+/// middle would manage
+fn binomial_cut<R: Rng + ?Sized>(n: usize, rng: &mut R) -> usize {
+    (0..n).filter(|_| rng.gen::<bool>()).count()
+}
+
+/// Split the cards at the cuts and stack the packets last first
+fn reverse_packets(cards: &mut Vec<Card>, cuts: &[usize]) {
+    let mut bounds = vec![0];
+    bounds.extend_from_slice(cuts);
+    bounds.push(cards.len());
+
+    let packets: Vec<Vec<Card>> = bounds.windows(2).map(|w| cards[w[0]..w[1]].to_vec()).collect();
+    *cards = packets.into_iter().rev().flatten().collect();
+}
+
+/// Deal one at a time into piles, then stack the first pile on top
+pub(crate) fn deal_piles<T>(cards: &mut Vec<T>, piles: usize) {
+    let piles = piles.max(1);
+    let mut dealt: Vec<Vec<T>> = (0..piles).map(|_| Vec::new()).collect();
+    for (i, card) in cards.drain(..).enumerate() {
+        dealt[i % piles].push(card);
+    }
+    for pile in dealt.into_iter() {
+        cards.extend(pile.into_iter().rev());
+    }
+}
+
+/// Split off the top `half` cards and weave the two halves perfectly
+pub(crate) fn faro<T>(cards: &mut Vec<T>, half: usize, top_first: bool) {
+    let bottom = cards.split_off(half);
+    let top = std::mem::take(cards);
+    let (first, second) = if top_first { (top, bottom) } else { (bottom, top) };
+
+    let mut second = second.into_iter();
+    for card in first.into_iter() {
+        cards.push(card);
+        cards.extend(second.next());
+    }
+    cards.extend(second);
+}
+
+#[test]
+fn physical_shuffles() {
+    use rand::SeedableRng;
+    let mut rng = rand_pcg::Pcg64Mcg::seed_from_u64(1);
+    let fresh = Deck::default();
+
+    // Eight out faros bring a deck back to new deck order
+    let mut deck = fresh.clone();
+    deck.mix_with(&[Mix::OutFaro; 7], &mut rng);
+    assert_ne!(deck, fresh);
+    deck.mix_with(&[Mix::OutFaro], &mut rng);
+    assert_eq!(deck, fresh);
+
+    let mut deck = fresh.clone();
+    deck.mix_with(&[Mix::InFaro], &mut rng);
+    assert_eq!(deck.cards[1], fresh.cards[0]);
+    assert_eq!(deck.cards[0], fresh.cards[26]);
+
+    let mut hand: Hand = "AS 2S 3S 4S 5S 6S".parse().expect("hand");
+    hand.mix_with(&[Mix::Pile { piles: 3 }], &mut rng);
+    assert_eq!(hand, "4S AS 5S 2S 6S 3S".parse().expect("hand"));
+
+    // Seven riffles and a cut lose no cards and leave few in place
+    let mut deck = fresh.clone();
+    let mut routine = vec![Mix::Riffle; 7];
+    routine.extend(&[Mix::Overhand { packet: 5 }, Mix::Strip { packets: 4 }, Mix::Cut]);
+    deck.mix_with(&routine, &mut rng);
+    assert!(Audit::default().check(Census::new().count("deck", &deck)).is_ok());
+    assert!(deck.cards.iter().zip(fresh.cards.iter()).filter(|(a, b)| a == b).count() < 10);
+}
@@ -0,0 +1,256 @@
+//! How well a shuffle mixes, measured over many trials
+
+use super::*;
+use std::collections::HashMap;
+
+/// A chi-square statistic and its degrees of freedom
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChiSquare {
+    pub statistic: f64,
+    pub dof: usize,
+}
+
+impl ChiSquare {
+    /// Compare observed counts with expected ones, pooling bins expecting
+    /// fewer than five so the approximation holds
+    pub fn test(observed: &[f64], expected: &[f64]) -> Self {
+        let mut statistic = 0.0;
+        let mut bins: usize = 0;
+        let (mut o, mut e) = (0.0, 0.0);
+
+        for (obs, exp) in observed.iter().zip(expected.iter()) {
+            o += obs;
+            e += exp;
+            if e >= 5.0 {
+                statistic += (o - e) * (o - e) / e;
+                bins += 1;
+                o = 0.0;
+                e = 0.0;
+            }
+        }
+        if e > 0.0 {
+            statistic += (o - e) * (o - e) / e;
+            bins += 1;
+        }
+
+        ChiSquare { statistic, dof: bins.saturating_sub(1).max(1) }
+    }
+
+    /// The chance of a statistic at least this large if the counts really
+    /// follow the expected distribution, by the Wilson–Hilferty approximation
+    pub fn p_value(&self) -> f64 {
+        let k = self.dof as f64;
+        let z = ((self.statistic / k).cbrt() - (1.0 - 2.0 / (9.0 * k))) / (2.0 / (9.0 * k)).sqrt();
+        1.0 - normal_cdf(z)
+    }
+}
+
+fn normal_cdf(z: f64) -> f64 {
+    // Abramowitz and Stegun 7.1.26
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+/// Where the cards ended up over many shuffles of the same starting order
+#[derive(Debug, Clone)]
+pub struct ShuffleStats {
+    pub trials: usize,
+    /// How many cards were shuffled
+    pub size: usize,
+    /// How often the card starting at each position ended at each position
+    pub positions: Vec<Vec<usize>>,
+    /// How often each number of rising sequences turned up, from one up
+    pub rising: Vec<usize>,
+    /// How many neighbouring pairs were still together, in order
+    pub pairs_kept: usize,
+    /// How often each order turned up, only kept for small decks
+    pub orders: HashMap<Vec<usize>, usize>,
+}
+
+/// The largest deck whose orders are all counted
+const MAX_ORDERS: usize = 8;
+
+impl ShuffleStats {
+    /// Shuffle copies of `start` and record where each card goes
+    ///
+    /// The cards in `start` must all be different.
+    pub fn collect<S, F>(start: &S, trials: usize, mut shuffle: F) -> Self
+    where
+        S: Clone + for<'a> Cards<'a>,
+        F: FnMut(&mut S),
+    {
+        let labels: HashMap<Card, usize> = start.cards().enumerate().map(|(i, c)| (*c, i)).collect();
+        let size = labels.len();
+        let mut stats = ShuffleStats {
+            trials,
+            size,
+            positions: vec![vec![0; size]; size],
+            // Like `uniform_rising`, no cards count as one rising sequence
+            rising: vec![0; size.max(1)],
+            pairs_kept: 0,
+            orders: HashMap::new(),
+        };
+
+        for _ in 0..trials {
+            let mut cards = start.clone();
+            shuffle(&mut cards);
+            let order: Vec<usize> = cards.cards().map(|c| labels[c]).collect();
+            stats.record(&order);
+        }
+
+        stats
+    }
+
+    fn record(&mut self, order: &[usize]) {
+        let mut position = vec![0; self.size];
+        for (at, label) in order.iter().enumerate() {
+            self.positions[*label][at] += 1;
+            position[*label] = at;
+        }
+
+        let descents = position.windows(2).filter(|w| w[1] < w[0]).count();
+        self.rising[descents] += 1;
+        self.pairs_kept += position.windows(2).filter(|w| w[1] == w[0] + 1).count();
+
+        if self.size <= MAX_ORDERS {
+            *self.orders.entry(order.to_vec()).or_insert(0) += 1;
+        }
+    }
+
+    /// Whether every card is as likely to end up in every position
+    pub fn position_chi_square(&self) -> ChiSquare {
+        let expected = self.trials as f64 / self.size as f64;
+        let statistic = self.positions
+            .iter()
+            .flatten()
+            .map(|count| (*count as f64 - expected).powi(2) / expected)
+            .sum();
+        ChiSquare { statistic, dof: (self.size.saturating_sub(1)).pow(2).max(1) }
+    }
+
+    /// The average number of rising sequences; a uniform shuffle of n cards
+    /// averages (n + 1) / 2
+    pub fn mean_rising(&self) -> f64 {
+        let total: usize = self.rising.iter().enumerate().map(|(i, count)| (i + 1) * count).sum();
+        total as f64 / self.trials as f64
+    }
+
+    /// The share of neighbouring pairs still together; about 1 / n when uniform
+    pub fn pair_survival(&self) -> f64 {
+        self.pairs_kept as f64 / (self.trials * self.size.saturating_sub(1)).max(1) as f64
+    }
+
+    /// The Bayer–Diaconis test: are the rising sequence counts those of a
+    /// uniform shuffle?
+    pub fn bayer_diaconis(&self) -> ChiSquare {
+        self.rising_chi_square(&uniform_rising(self.size))
+    }
+
+    /// Compare the rising sequence counts with a distribution, like
+    /// `riffle_rising(n, k)`
+    pub fn rising_chi_square(&self, distribution: &[f64]) -> ChiSquare {
+        let observed: Vec<f64> = self.rising.iter().map(|c| *c as f64).collect();
+        let expected: Vec<f64> = distribution.iter().map(|p| p * self.trials as f64).collect();
+        ChiSquare::test(&observed, &expected)
+    }
+
+    /// Estimated total variation distance from uniform, for decks of up to
+    /// eight cards
+    ///
+    /// Sampling noise pushes the estimate up, so use many more trials than
+    /// there are orders.
+    pub fn total_variation(&self) -> Option<f64> {
+        if self.size > MAX_ORDERS {
+            return None;
+        }
+
+        let orders: f64 = (1..=self.size).map(|n| n as f64).product();
+        let uniform = 1.0 / orders;
+        let seen: f64 = self.orders
+            .values()
+            .map(|count| (*count as f64 / self.trials as f64 - uniform).abs())
+            .sum();
+        let unseen = (orders - self.orders.len() as f64) * uniform;
+        Some((seen + unseen) / 2.0)
+    }
+}
+
+/// The chance of each number of rising sequences, from one up, after a
+/// uniform shuffle of `n` cards: the Eulerian numbers over n!
+pub fn uniform_rising(n: usize) -> Vec<f64> {
+    let mut eulerian = vec![1.0];
+    for m in 2..=n {
+        let mut next = vec![0.0; m];
+        for (d, p) in eulerian.iter().enumerate() {
+            next[d] += (d + 1) as f64 * p / m as f64;
+            next[d + 1] += (m - d - 1) as f64 * p / m as f64;
+        }
+        eulerian = next;
+    }
+    eulerian
+}
+
+/// The chance of each number of rising sequences, from one up, after `k`
+/// Gilbert–Shannon–Reeds riffles of `n` cards
+///
+/// Every order with r rising sequences is equally likely, with chance
+/// C(2^k + n - r, n) / 2^kn.
+pub fn riffle_rising(n: usize, k: u32) -> Vec<f64> {
+    let a = 2f64.powi(k as i32);
+    uniform_rising(n)
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let r = (i + 1) as f64;
+            p * (0..n).map(|j| ((a + n as f64 - r - j as f64) / a).max(0.0)).product::<f64>()
+        })
+        .collect()
+}
+
+/// The exact total variation distance from uniform after `k` riffles of
+/// `n` cards, from Bayer and Diaconis
+pub fn riffle_variation(n: usize, k: u32) -> f64 {
+    let uniform = uniform_rising(n);
+    riffle_rising(n, k)
+        .iter()
+        .zip(uniform.iter())
+        .map(|(p, u)| (p - u).abs())
+        .sum::<f64>()
+        / 2.0
+}
+
+#[test]
+fn shuffle_quality() {
+    use rand::SeedableRng;
+    let mut rng = rand_pcg::Pcg64Mcg::seed_from_u64(3);
+    let deck = Deck::default();
+
+    assert!((riffle_variation(52, 7) - 0.334).abs() < 0.001);
+    assert!((riffle_variation(52, 6) - 0.614).abs() < 0.001);
+
+    let uniform = ShuffleStats::collect(&deck, 2000, |d| d.mix_with(&[Mix::Uniform], &mut rng));
+    assert!(uniform.position_chi_square().p_value() > 0.001);
+    assert!(uniform.bayer_diaconis().p_value() > 0.001);
+    assert!((uniform.mean_rising() - 26.5).abs() < 0.5);
+    assert!(uniform.pair_survival() < 0.05);
+
+    let riffled = ShuffleStats::collect(&deck, 2000, |d| d.mix_with(&[Mix::Riffle; 3], &mut rng));
+    assert!(riffled.bayer_diaconis().p_value() < 1e-6);
+    assert!(riffled.rising_chi_square(&riffle_rising(52, 3)).p_value() > 0.001);
+
+    let hand: Hand = "AS 2S 3S 4S".parse().expect("hand");
+    let small = ShuffleStats::collect(&hand, 20_000, |h| h.mix_with(&[Mix::Uniform], &mut rng));
+    assert!(small.total_variation().expect("small deck") < 0.05);
+    let faro = ShuffleStats::collect(&hand, 100, |h| h.mix_with(&[Mix::OutFaro], &mut rng));
+    assert!(faro.total_variation().expect("small deck") > 0.9);
+
+    let empty = ShuffleStats::collect(&Hand::new(), 10, |h| h.mix_with(&[Mix::Riffle], &mut rng));
+    assert_eq!(empty.rising, vec![10]);
+}
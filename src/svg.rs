@@ -0,0 +1,231 @@
+//! Standalone SVG drawings of cards, hands and tables
+
+use super::*;
+use std::fmt::Write;
+
+/// The height of a row label above a row of cards
+const LABEL_HEIGHT: u32 = 20;
+
+/// The space around and between rows of cards
+const MARGIN: u32 = 10;
+
+/// Draws cards as SVG documents
+#[derive(Debug, Copy, Clone)]
+pub struct Svg {
+    pub card_width: u32,
+    pub card_height: u32,
+    pub spread: Spread,
+}
+
+impl Default for Svg {
+    fn default() -> Self {
+        Svg::new(70, 100, Spread::Fan)
+    }
+}
+
+impl Svg {
+    pub fn new(card_width: u32, card_height: u32, spread: Spread) -> Self {
+        Svg {
+            card_width,
+            card_height,
+            spread,
+        }
+    }
+
+    /// A document holding a single card
+    pub fn card(&self, face: &Face) -> String {
+        self.faces(&[*face])
+    }
+
+    /// A document holding one row of cards
+    pub fn faces(&self, faces: &[Face]) -> String {
+        let width = self.row_width(faces.len()) + 2 * MARGIN;
+        let height = self.card_height + 2 * MARGIN;
+
+        let mut body = String::new();
+        self.row(&mut body, MARGIN, MARGIN, faces);
+        document(width, height, &body)
+    }
+
+    /// A document holding every card face up
+    pub fn cards<'a, C: Cards<'a>>(&self, cards: &'a C) -> String {
+        let faces: Vec<Face> = cards.cards().map(|c| Face::Up(*c)).collect();
+        self.faces(&faces)
+    }
+
+    /// A document with a labelled row of cards for each seat
+    pub fn table(&self, rows: &[(String, Vec<Face>)]) -> String {
+        let row_height = LABEL_HEIGHT + self.card_height + MARGIN;
+        let widest = rows.iter().map(|(_, faces)| faces.len()).max().unwrap_or(0);
+        let width = self.row_width(widest).max(self.card_width) + 2 * MARGIN;
+        let height = row_height * rows.len() as u32 + MARGIN;
+
+        let mut body = String::new();
+        for (i, (label, faces)) in rows.iter().enumerate() {
+            let y = MARGIN + row_height * i as u32;
+            writeln!(
+                body,
+                r#"<text x="{}" y="{}" font-family="sans-serif" font-size="14">{}</text>"#,
+                MARGIN,
+                y + LABEL_HEIGHT - 6,
+                escape(label),
+            )
+            .expect("write");
+            self.row(&mut body, MARGIN, y + LABEL_HEIGHT, faces);
+        }
+
+        document(width, height, &body)
+    }
+
+    /// A snapshot of a game: the deck as a face down pile and every hand
+    /// face up
+    pub fn game(&self, game: &Game) -> String {
+        let pile = if game.deck.is_empty() { vec![] } else { vec![Face::Down] };
+        let mut rows = vec![(format!("Deck ({})", game.deck.len()), pile)];
+        if !game.kitty.is_empty() {
+            rows.push((format!("Kitty ({})", game.kitty.len()), vec![Face::Down]));
+        }
+
+        for (i, hand) in game.hands.iter().enumerate() {
+            let faces = hand.cards().map(|c| Face::Up(*c)).collect();
+            rows.push((format!("Hand {}", i), faces));
+        }
+
+        self.table(&rows)
+    }
+
+    /// How far apart neighbouring cards are
+    fn step(&self) -> u32 {
+        match self.spread {
+            Spread::Row => self.card_width + MARGIN,
+            Spread::Fan => self.card_width / 3,
+            Spread::Stack => 2,
+        }
+    }
+
+    fn row_width(&self, count: usize) -> u32 {
+        match count {
+            0 => 0,
+            n => self.step() * (n as u32 - 1) + self.card_width,
+        }
+    }
+
+    fn row(&self, body: &mut String, x: u32, y: u32, faces: &[Face]) {
+        for (i, face) in faces.iter().enumerate() {
+            self.glyph(body, x + self.step() * i as u32, y, face);
+        }
+    }
+
+    fn glyph(&self, body: &mut String, x: u32, y: u32, face: &Face) {
+        let (w, h) = (self.card_width, self.card_height);
+        let radius = w / 10;
+
+        writeln!(body, r#"<g transform="translate({},{})">"#, x, y).expect("write");
+
+        match face {
+            Face::Up(card) => {
+                let color = match card.suit {
+                    Suit::Hearts | Suit::Diamonds => "#c00000",
+                    Suit::Clubs | Suit::Spades => "#000000",
+                };
+                let index = format!("{}{}", card.value, card.suit);
+                let small = h / 6;
+
+                writeln!(
+                    body,
+                    r##"<rect width="{}" height="{}" rx="{}" fill="#ffffff" stroke="#000000"/>"##,
+                    w, h, radius,
+                )
+                .expect("write");
+                writeln!(
+                    body,
+                    r#"<g fill="{}" font-family="sans-serif">"#,
+                    color,
+                )
+                .expect("write");
+                writeln!(
+                    body,
+                    r#"<text x="{}" y="{}" font-size="{}">{}</text>"#,
+                    radius, small + radius / 2, small, index,
+                )
+                .expect("write");
+                writeln!(
+                    body,
+                    r#"<text x="{}" y="{}" font-size="{}" text-anchor="middle">{}</text>"#,
+                    w / 2, h / 2 + h / 8, h / 3, card.suit,
+                )
+                .expect("write");
+                writeln!(
+                    body,
+                    r#"<text x="{}" y="{}" font-size="{}" transform="rotate(180 {} {})">{}</text>"#,
+                    radius, small + radius / 2, small, w / 2, h / 2, index,
+                )
+                .expect("write");
+                writeln!(body, "</g>").expect("write");
+            }
+            Face::Down => {
+                let inset = radius / 2 + 2;
+                writeln!(
+                    body,
+                    r##"<rect width="{}" height="{}" rx="{}" fill="#ffffff" stroke="#000000"/>"##,
+                    w, h, radius,
+                )
+                .expect("write");
+                writeln!(
+                    body,
+                    r##"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" fill="#1f4e8c"/>"##,
+                    inset, inset, w - 2 * inset, h - 2 * inset, radius / 2,
+                )
+                .expect("write");
+            }
+        }
+
+        writeln!(body, "</g>").expect("write");
+    }
+}
+
+fn document(width: u32, height: u32, body: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n{}</svg>\n",
+        body,
+        w = width,
+        h = height,
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[test]
+fn svg_golden() {
+    let svg = Svg::new(70, 100, Spread::Fan);
+    let ten = Card::new(Value::Ten, Suit::Hearts);
+
+    assert_eq!(
+        svg.faces(&[ten.into(), Face::Down]),
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="113" height="120" viewBox="0 0 113 120">
+<g transform="translate(10,10)">
+<rect width="70" height="100" rx="7" fill="#ffffff" stroke="#000000"/>
+<g fill="#c00000" font-family="sans-serif">
+<text x="7" y="19" font-size="16">10♥</text>
+<text x="35" y="62" font-size="33" text-anchor="middle">♥</text>
+<text x="7" y="19" font-size="16" transform="rotate(180 35 50)">10♥</text>
+</g>
+</g>
+<g transform="translate(33,10)">
+<rect width="70" height="100" rx="7" fill="#ffffff" stroke="#000000"/>
+<rect x="5" y="5" width="60" height="90" rx="3" fill="#1f4e8c"/>
+</g>
+</svg>
+"##
+    );
+
+    let game = Game::new(2, 3).expect("deal");
+    let table = svg.game(&game);
+    assert!(table.contains("Deck (46)"));
+    assert_eq!(table.matches("<rect").count(), 2 + 3 + 3);
+}
@@ -0,0 +1,269 @@
+//! A card table: seats around it, named zones of cards and a dealer button
+
+use super::*;
+
+/// A zone's place on the table
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZoneId(pub usize);
+
+/// A named pile of cards, shared or belonging to one seat
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Zone {
+    pub name: String,
+    pub seat: Option<usize>,
+    pub cards: Hand,
+}
+
+/// Someone sitting at the table
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableSeat {
+    pub name: String,
+    pub team: Option<usize>,
+}
+
+/// Anything that names a zone: its id, a shared zone's name like "stock",
+/// or a seat and a name like `(2, "hand")`
+pub trait ZoneKey {
+    fn zone_id(&self, table: &Table) -> Option<ZoneId>;
+}
+
+impl ZoneKey for ZoneId {
+    fn zone_id(&self, table: &Table) -> Option<ZoneId> {
+        if self.0 < table.zones.len() {
+            Some(*self)
+        } else {
+            None
+        }
+    }
+}
+
+impl ZoneKey for &str {
+    fn zone_id(&self, table: &Table) -> Option<ZoneId> {
+        table.find(|zone| zone.seat.is_none() && zone.name == *self)
+    }
+}
+
+impl ZoneKey for (usize, &str) {
+    fn zone_id(&self, table: &Table) -> Option<ZoneId> {
+        table.find(|zone| zone.seat == Some(self.0) && zone.name == self.1)
+    }
+}
+
+/// Seats, the zones of cards between them and the dealer button
+///
+/// A new table has a shuffled deck in the "stock", an empty "discard" and an
+/// empty "hand" for every seat. Games add whatever else they need, like a
+/// "kitty" or each seat's "melds".
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Table {
+    pub seats: Vec<TableSeat>,
+    zones: Vec<Zone>,
+    /// The seat with the dealer button
+    pub dealer: usize,
+}
+
+impl Table {
+    pub fn new(names: &[&str]) -> Self {
+        Table::with_deck(names, Deck::new())
+    }
+
+    /// A table with this deck in the stock, in this order
+    pub fn with_deck(names: &[&str], deck: Deck) -> Self {
+        let mut table = Table {
+            seats: names
+                .iter()
+                .map(|name| TableSeat { name: String::from(*name), team: None })
+                .collect(),
+            zones: Vec::new(),
+            dealer: 0,
+        };
+
+        let stock = table.add_zone("stock");
+        table.zones[stock.0].cards = Hand::from(deck.cards);
+        table.add_zone("discard");
+        for seat in 0..names.len() {
+            table.add_seat_zone(seat, "hand");
+        }
+
+        table
+    }
+
+    /// Add a shared zone, or find it if it is already there
+    pub fn add_zone(&mut self, name: &str) -> ZoneId {
+        match name.zone_id(self) {
+            Some(id) => id,
+            None => self.push(name, None),
+        }
+    }
+
+    /// Add a zone belonging to a seat, or find it if it is already there
+    pub fn add_seat_zone(&mut self, seat: usize, name: &str) -> ZoneId {
+        match (seat, name).zone_id(self) {
+            Some(id) => id,
+            None => self.push(name, Some(seat)),
+        }
+    }
+
+    /// Add the zone to every seat
+    pub fn add_seat_zones(&mut self, name: &str) -> Vec<ZoneId> {
+        (0..self.seats.len()).map(|seat| self.add_seat_zone(seat, name)).collect()
+    }
+
+    pub fn id<K: ZoneKey>(&self, key: K) -> Option<ZoneId> {
+        key.zone_id(self)
+    }
+
+    pub fn zone<K: ZoneKey>(&self, key: K) -> Option<&Zone> {
+        key.zone_id(self).map(|id| &self.zones[id.0])
+    }
+
+    pub fn zone_mut<K: ZoneKey>(&mut self, key: K) -> Option<&mut Zone> {
+        key.zone_id(self).map(move |id| &mut self.zones[id.0])
+    }
+
+    /// The cards in a zone
+    pub fn cards<K: ZoneKey>(&self, key: K) -> Option<&Hand> {
+        self.zone(key).map(|zone| &zone.cards)
+    }
+
+    pub fn zones(&self) -> impl Iterator<Item = (ZoneId, &Zone)> {
+        self.zones.iter().enumerate().map(|(i, zone)| (ZoneId(i), zone))
+    }
+
+    /// Move the card at `index` in one zone to the end of another
+    pub fn move_card<F: ZoneKey, T: ZoneKey>(&mut self, from: F, index: usize, to: T) -> DealResult<Card> {
+        let from = from.zone_id(self).ok_or(DealError::OutOfBounds)?;
+        let to = to.zone_id(self).ok_or(DealError::OutOfBounds)?;
+
+        let card = self.zones[from.0].cards.give(index)?;
+        self.zones[to.0].cards.take(card);
+        Ok(card)
+    }
+
+    /// Move `count` cards from the top of one zone to another
+    pub fn move_cards<F: ZoneKey, T: ZoneKey>(&mut self, from: F, count: usize, to: T) -> DealResult<()> {
+        let from = from.zone_id(self).ok_or(DealError::OutOfBounds)?;
+        let to = to.zone_id(self).ok_or(DealError::OutOfBounds)?;
+
+        if self.zones[from.0].cards.len() < count {
+            return Err(DealError::NothingToGive);
+        }
+        for _ in 0..count {
+            self.move_card(from, 0, to)?;
+        }
+        Ok(())
+    }
+
+    /// Put a seat on a team
+    pub fn set_team(&mut self, seat: usize, team: usize) {
+        self.seats[seat].team = Some(team);
+    }
+
+    /// Seat partners across the table: 0 with 2, 1 with 3, and so on
+    pub fn partnerships(&mut self) {
+        let teams = (self.seats.len() / 2).max(1);
+        for seat in 0..self.seats.len() {
+            self.set_team(seat, seat % teams);
+        }
+    }
+
+    /// The other seats on the same team
+    pub fn partners(&self, seat: usize) -> Vec<usize> {
+        match self.seats[seat].team {
+            Some(team) => (0..self.seats.len())
+                .filter(|s| *s != seat && self.seats[*s].team == Some(team))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The seat `n` places to the left of `seat`, or `None` at an empty
+    /// table
+    pub fn left_of(&self, seat: usize, n: usize) -> Option<usize> {
+        match self.seats.len() {
+            0 => None,
+            len => Some((seat + n) % len),
+        }
+    }
+
+    /// Pass the dealer button to the left, ready for the next deal
+    pub fn rotate_dealer(&mut self) -> Option<usize> {
+        self.dealer = self.left_of(self.dealer, 1)?;
+        Some(self.dealer)
+    }
+
+    /// Turns starting with the seat `n` places left of the dealer
+    pub fn turn_order(&self, n: usize) -> TurnOrder {
+        TurnOrder::left_of_dealer(self.seats.len(), self.dealer, n)
+    }
+
+    /// Count every zone for an `Audit`
+    pub fn census(&self) -> Census {
+        let mut census = Census::new();
+        for zone in self.zones.iter() {
+            match zone.seat {
+                Some(seat) => census.count(&format!("{} {}", self.seats[seat].name, zone.name), &zone.cards),
+                None => census.count(&zone.name, &zone.cards),
+            };
+        }
+        census
+    }
+
+    fn find<P: Fn(&Zone) -> bool>(&self, predicate: P) -> Option<ZoneId> {
+        self.zones.iter().position(predicate).map(ZoneId)
+    }
+
+    fn push(&mut self, name: &str, seat: Option<usize>) -> ZoneId {
+        self.zones.push(Zone {
+            name: name.into(),
+            seat,
+            cards: Hand::new(),
+        });
+        ZoneId(self.zones.len() - 1)
+    }
+}
+
+/// Zones are numbered in the order they were added
+impl Piles for Table {
+    fn pile(&mut self, id: usize) -> Option<&mut dyn Pile> {
+        self.zones.get_mut(id).map(|zone| &mut zone.cards as &mut dyn Pile)
+    }
+}
+
+#[test]
+fn table_zones_and_dealer() -> DealResult<()> {
+    let mut table = Table::with_deck(&["N", "E", "S", "W"], Deck::default());
+    table.partnerships();
+    assert_eq!(table.partners(0), vec![2]);
+    assert_eq!(table.partners(3), vec![1]);
+
+    let kitty = table.add_zone("kitty");
+    table.add_seat_zones("melds");
+    assert_eq!(table.add_zone("kitty"), kitty);
+
+    table.move_cards("stock", 5, (1, "hand"))?;
+    table.move_card((1, "hand"), 0, kitty)?;
+    table.move_card((1, "hand"), 0, (1, "melds"))?;
+    assert_eq!(table.cards("stock").map(Hand::len), Some(47));
+    assert_eq!(table.cards((1, "hand")).map(Hand::len), Some(3));
+    assert_eq!(table.cards(kitty).and_then(|k| k.cards().next().copied()), Some(Card::default()));
+    assert!(table.move_card("tricks", 0, "discard").is_err());
+    assert!(Audit::default().check(&table.census()).is_ok());
+
+    assert_eq!(table.rotate_dealer(), Some(1));
+    assert_eq!(table.left_of(table.dealer, 1), Some(2));
+    assert_eq!(table.turn_order(1).current(), 2);
+    table.rotate_dealer();
+    table.rotate_dealer();
+    assert_eq!(table.rotate_dealer(), Some(0));
+
+    let mut empty = Table::with_deck(&[], Deck::default());
+    assert_eq!(empty.left_of(0, 1), None);
+    assert_eq!(empty.rotate_dealer(), None);
+
+    Ok(())
+}
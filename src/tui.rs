@@ -0,0 +1,289 @@
+//! A full-screen terminal front-end for card games
+//!
+//! A `View` describes what is on screen and lays itself out as plain lines,
+//! so games can build and test it headless. A `Terminal` draws a `View` and
+//! reads keys from the real terminal.
+
+use super::*;
+use crossterm::{cursor, event, execute, queue, style, terminal};
+use std::io::{self, Write};
+
+/// A labelled row of cards on the table
+#[derive(Debug, Clone)]
+pub struct Seat {
+    pub label: String,
+    pub faces: Vec<Face>,
+    pub spread: Spread,
+    /// The highlighted card, if any
+    pub selected: Option<usize>,
+}
+
+impl Seat {
+    pub fn new(label: &str, faces: Vec<Face>, spread: Spread) -> Self {
+        Seat {
+            label: label.into(),
+            faces,
+            spread,
+            selected: None,
+        }
+    }
+
+    fn lines(&self, width: usize) -> Vec<String> {
+        let renderer = Renderer::new(width, self.spread);
+        let per_row = renderer.per_row();
+        let step = self.spread.step();
+        let mut lines = vec![self.label.clone()];
+
+        for (row, chunk) in self.faces.chunks(per_row).enumerate() {
+            let mut canvas = vec![vec![' '; width.max(CARD_WIDTH)]; CARD_HEIGHT];
+            let mut top = None;
+
+            for (i, face) in chunk.iter().enumerate() {
+                let glyph = renderer.glyph(face);
+                if self.selected == Some(row * per_row + i) {
+                    top = Some((i, highlight(glyph)));
+                } else {
+                    paint(&mut canvas, i * step, &glyph);
+                }
+            }
+
+            // The selected card is drawn last so it sits on top of the others
+            if let Some((i, glyph)) = top {
+                paint(&mut canvas, i * step, &glyph);
+            }
+
+            for line in canvas {
+                lines.push(line.into_iter().collect::<String>().trim_end().into());
+            }
+        }
+
+        lines
+    }
+}
+
+fn paint(canvas: &mut [Vec<char>], x: usize, glyph: &[String]) {
+    for (line, part) in canvas.iter_mut().zip(glyph.iter()) {
+        for (col, c) in part.chars().enumerate() {
+            if let Some(cell) = line.get_mut(x + col) {
+                *cell = c;
+            }
+        }
+    }
+}
+
+fn highlight(glyph: Vec<String>) -> Vec<String> {
+    glyph
+        .into_iter()
+        .map(|line| line.replace('-', "=").replace('+', "*"))
+        .collect()
+}
+
+/// Everything on screen: the table, an event log and a status bar
+#[derive(Debug, Clone, Default)]
+pub struct View {
+    pub title: String,
+    pub seats: Vec<Seat>,
+    pub log: Vec<String>,
+    pub status: String,
+}
+
+impl View {
+    pub fn new(title: &str) -> Self {
+        View {
+            title: title.into(),
+            ..View::default()
+        }
+    }
+
+    /// Lay out the view as exactly `height` lines of at most `width` columns
+    ///
+    /// The table fills the left of the screen and the most recent log
+    /// entries fill a pane on the right. The status bar is the last line.
+    pub fn lines(&self, width: usize, height: usize) -> Vec<String> {
+        let log_width = (width / 3).max(20).min(width);
+        let table_width = width.saturating_sub(log_width + 1);
+        let body = height.saturating_sub(2);
+
+        let mut table: Vec<String> = self
+            .seats
+            .iter()
+            .flat_map(|seat| {
+                let mut lines = seat.lines(table_width);
+                lines.push(String::new());
+                lines
+            })
+            .collect();
+        table.resize(body, String::new());
+
+        let mut log = vec![String::from("Log")];
+        let recent = self.log.len().saturating_sub(body.saturating_sub(1));
+        log.extend(self.log[recent..].iter().cloned());
+        log.resize(body, String::new());
+
+        let mut lines = vec![clip(&self.title, width)];
+        for (left, right) in table.iter().zip(log.iter()) {
+            let line = format!(
+                "{:<w$}|{}",
+                clip(left, table_width),
+                clip(right, log_width),
+                w = table_width,
+            );
+            lines.push(line);
+        }
+        lines.push(clip(&self.status, width));
+        lines.truncate(height);
+
+        lines
+    }
+}
+
+fn clip(s: &str, width: usize) -> String {
+    s.chars().take(width).collect()
+}
+
+/// A key press, reduced to what the games care about
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Key {
+    Left,
+    Right,
+    Up,
+    Down,
+    Enter,
+    Quit,
+    Char(char),
+}
+
+/// The real terminal: raw mode on an alternate screen, restored on drop
+pub struct Terminal {
+    out: io::Stdout,
+}
+
+impl Terminal {
+    pub fn new() -> io::Result<Self> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Terminal { out })
+    }
+
+    pub fn draw(&mut self, view: &View) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let lines = view.lines(width as usize, height as usize);
+        let last = lines.len().saturating_sub(1);
+
+        for (row, line) in lines.iter().enumerate() {
+            queue!(self.out, cursor::MoveTo(0, row as u16))?;
+            if row == last {
+                queue!(self.out, style::SetAttribute(style::Attribute::Reverse))?;
+                queue!(self.out, style::Print(format!("{:<1$}", line, width as usize)))?;
+                queue!(self.out, style::SetAttribute(style::Attribute::Reset))?;
+            } else {
+                queue!(self.out, style::Print(line))?;
+                queue!(self.out, terminal::Clear(terminal::ClearType::UntilNewLine))?;
+            }
+        }
+
+        self.out.flush()
+    }
+
+    /// Block until the next key press
+    pub fn key(&mut self) -> io::Result<Key> {
+        use event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+
+        loop {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Release {
+                    continue;
+                }
+
+                let key = match key.code {
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Key::Quit,
+                    KeyCode::Left | KeyCode::Char('h') => Key::Left,
+                    KeyCode::Right | KeyCode::Char('l') => Key::Right,
+                    KeyCode::Up | KeyCode::Char('k') => Key::Up,
+                    KeyCode::Down | KeyCode::Char('j') => Key::Down,
+                    KeyCode::Enter | KeyCode::Char(' ') => Key::Enter,
+                    KeyCode::Esc | KeyCode::Char('q') => Key::Quit,
+                    KeyCode::Char(c) => Key::Char(c),
+                    _ => continue,
+                };
+
+                return Ok(key);
+            }
+        }
+    }
+
+    /// Let the user pick a card from one seat with the arrow keys
+    ///
+    /// Returns `None` if the user quits instead.
+    pub fn select(&mut self, view: &mut View, seat: usize) -> io::Result<Option<usize>> {
+        match self.choose(view, seat, &[])? {
+            Choice::Card(index) => Ok(Some(index)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Like `select`, but any of `keys` can be pressed instead of picking a
+    /// card
+    pub fn choose(&mut self, view: &mut View, seat: usize, keys: &[char]) -> io::Result<Choice> {
+        let count = view.seats[seat].faces.len();
+        if count == 0 {
+            return Ok(Choice::Quit);
+        }
+
+        let mut index = view.seats[seat].selected.unwrap_or(0).min(count - 1);
+
+        let choice = loop {
+            view.seats[seat].selected = Some(index);
+            self.draw(view)?;
+
+            match self.key()? {
+                Key::Left | Key::Up => index = (index + count - 1) % count,
+                Key::Right | Key::Down => index = (index + 1) % count,
+                Key::Enter => break Choice::Card(index),
+                Key::Quit => break Choice::Quit,
+                Key::Char(c) if keys.contains(&c) => break Choice::Key(c),
+                Key::Char(_) => (),
+            }
+        };
+
+        view.seats[seat].selected = None;
+        Ok(choice)
+    }
+}
+
+/// What the user did when asked to pick a card
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Choice {
+    Card(usize),
+    /// One of the extra keys on offer
+    Key(char),
+    Quit,
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(self.out, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[test]
+fn view_layout() {
+    let mut view = View::new("Test");
+    let mut seat = Seat::new("Hand", vec![Face::Down, Face::Down], Spread::Fan);
+    seat.selected = Some(0);
+    view.seats.push(seat);
+    view.log.push("Dealt".into());
+    view.status = "Your turn".into();
+
+    let lines = view.lines(60, 10);
+    assert_eq!(lines.len(), 10);
+    assert_eq!(lines[0], "Test");
+    assert_eq!(lines[9], "Your turn");
+
+    // The selected card is drawn on top of the one beside it
+    assert!(lines[2].starts_with("*=====*---+"));
+    assert!(lines[1].ends_with("|Log"));
+    assert!(lines[2].ends_with("|Dealt"));
+}
@@ -0,0 +1,189 @@
+//! Whose turn it is, and whose it will be
+
+/// Which way play passes around the table
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    /// To the left, the way cards are dealt
+    Clockwise,
+    Counterclockwise,
+}
+
+impl Direction {
+    pub fn reversed(self) -> Self {
+        match self {
+            Direction::Clockwise => Direction::Counterclockwise,
+            Direction::Counterclockwise => Direction::Clockwise,
+        }
+    }
+}
+
+/// The order that seats take their turns in
+///
+/// Skips and extra turns wait until the next `advance`. Eliminated seats
+/// are passed over for good.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TurnOrder {
+    current: usize,
+    pub direction: Direction,
+    active: Vec<bool>,
+    skips: usize,
+    extra_turns: usize,
+}
+
+impl TurnOrder {
+    /// Seat 0 plays first, then play passes clockwise
+    pub fn new(seats: usize) -> Self {
+        TurnOrder {
+            current: 0,
+            direction: Direction::Clockwise,
+            active: vec![true; seats],
+            skips: 0,
+            extra_turns: 0,
+        }
+    }
+
+    /// The seat `n` places left of the dealer plays first
+    pub fn left_of_dealer(seats: usize, dealer: usize, n: usize) -> Self {
+        let mut turns = TurnOrder::new(seats);
+        turns.current = (dealer + n) % seats.max(1);
+        turns
+    }
+
+    /// The seat whose turn it is
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// The next seat round the table, counting skips but not extra turns,
+    /// so it is never the current seat while another is still playing
+    pub fn next(&self) -> usize {
+        let mut turns = self.clone();
+        turns.extra_turns = 0;
+        turns.advance()
+    }
+
+    /// The seat whose turn it will be `n` turns from now
+    pub fn peek(&self, n: usize) -> usize {
+        let mut turns = self.clone();
+        for _ in 0..n {
+            turns.advance();
+        }
+        turns.current
+    }
+
+    /// Every seat still playing except the current one, in turn order
+    ///
+    /// These are the seats a player could ask or target.
+    pub fn opponents(&self) -> Vec<usize> {
+        let mut seats = Vec::new();
+        let mut seat = self.step(self.current);
+        while seat != self.current && seats.len() < self.active.len() {
+            seats.push(seat);
+            seat = self.step(seat);
+        }
+        seats
+    }
+
+    /// How many seats there are, playing or not
+    pub fn seats(&self) -> usize {
+        self.active.len()
+    }
+
+    /// How many seats are still playing
+    pub fn remaining(&self) -> usize {
+        self.active.iter().filter(|a| **a).count()
+    }
+
+    pub fn is_active(&self, seat: usize) -> bool {
+        self.active.get(seat).copied().unwrap_or(false)
+    }
+
+    /// Pass the turn on, returning the seat that plays now
+    pub fn advance(&mut self) -> usize {
+        if self.extra_turns > 0 && self.is_active(self.current) {
+            self.extra_turns -= 1;
+            return self.current;
+        }
+        self.extra_turns = 0;
+
+        let mut seat = self.step(self.current);
+        for _ in 0..self.skips {
+            seat = self.step(seat);
+        }
+        self.skips = 0;
+        self.current = seat;
+        seat
+    }
+
+    /// Change the direction of play, like an Uno reverse
+    pub fn reverse(&mut self) {
+        self.direction = self.direction.reversed();
+    }
+
+    /// The next seat to play misses its turn
+    pub fn skip(&mut self) {
+        self.skips += 1;
+    }
+
+    /// The current seat plays again after this turn
+    pub fn extra_turn(&mut self) {
+        self.extra_turns += 1;
+    }
+
+    /// Take a seat out of the game; it never plays again
+    pub fn eliminate(&mut self, seat: usize) {
+        if let Some(active) = self.active.get_mut(seat) {
+            *active = false;
+        }
+    }
+
+    /// The next active seat after `seat` in the direction of play
+    fn step(&self, seat: usize) -> usize {
+        let n = self.active.len();
+        let mut next = seat;
+        for _ in 0..n {
+            next = match self.direction {
+                Direction::Clockwise => (next + 1) % n,
+                Direction::Counterclockwise => (next + n - 1) % n,
+            };
+            if self.active[next] {
+                return next;
+            }
+        }
+        seat
+    }
+}
+
+#[test]
+fn turn_order() {
+    let mut turns = TurnOrder::left_of_dealer(4, 3, 1);
+    assert_eq!(turns.current(), 0);
+    assert_eq!(turns.peek(5), 1);
+    assert_eq!(turns.opponents(), vec![1, 2, 3]);
+
+    turns.reverse();
+    assert_eq!(turns.advance(), 3);
+    turns.skip();
+    assert_eq!(turns.next(), 1);
+    assert_eq!(turns.advance(), 1);
+
+    turns.extra_turn();
+    assert_eq!(turns.next(), 0);
+    assert_eq!(turns.peek(1), 1);
+    assert_eq!(turns.peek(2), 0);
+    assert_eq!(turns.advance(), 1);
+
+    turns.eliminate(0);
+    turns.reverse();
+    assert_eq!(turns.opponents(), vec![2, 3]);
+    assert_eq!(turns.advance(), 2);
+    assert_eq!(turns.peek(2), 1);
+    assert_eq!(turns.remaining(), 3);
+
+    turns.eliminate(1);
+    turns.eliminate(3);
+    assert_eq!(turns.advance(), 2);
+    assert!(turns.opponents().is_empty());
+}